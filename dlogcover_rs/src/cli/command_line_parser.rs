@@ -16,18 +16,45 @@ pub struct CliOptions {
     #[arg(short, long, value_name = "FILE_PATH")]
     pub config: Option<String>,
 
-    /// Excludes files or directories matching the specified pattern. Can be used multiple times.
+    /// Excludes files or directories matching the specified glob pattern (e.g. `*.tmp`,
+    /// `**/test/**`). Can be used multiple times; a later `!`-prefixed pattern re-includes
+    /// anything excluded by an earlier one.
     #[arg(short, long, value_name = "PATTERN", num_args = 0..)]
     pub exclude: Vec<String>,
 
-    /// Sets the logging level (e.g., error, warn, info, debug, trace).
+    /// Sets the logging level (e.g., error, warn, info, debug, trace). Overrides `-v`/`-q`.
     #[arg(short = 'L', long = "log-level", value_name = "LEVEL")]
     pub log_level: Option<String>,
 
+    /// Raises the logging level; repeatable (-v = debug, -vv = trace).
+    #[arg(short = 'v', long = "verbose", action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Lowers the logging level; repeatable (-q = warn, -qq or more = error).
+    #[arg(short = 'q', long = "quiet", action = clap::ArgAction::Count)]
+    pub quiet: u8,
+
     /// Sets the output format for reports (e.g., text, json, html).
     #[arg(short, long, value_name = "FORMAT")]
     pub format: Option<String>,
 
+    /// Selects a named analysis profile defined in the config's `profiles` section (e.g. "ci",
+    /// "quick"). Applied after the config file loads but before CLI flags, so other CLI flags
+    /// still override it.
+    #[arg(short = 'P', long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Restricts which construct types are collected: "functions", "branches", "exceptions", or
+    /// "all" (default).
+    #[arg(short = 'M', long, value_name = "MODE")]
+    pub mode: Option<String>,
+
+    /// Merges this run's coverage with one or more prior JSON reports (as produced by
+    /// `--format json`) before generating the final report. Can be used multiple times; see
+    /// `core::coverage::merge` for how per-file items are reconciled across runs.
+    #[arg(long = "merge-with", value_name = "JSON_FILE", num_args = 0..)]
+    pub merge_with: Vec<String>,
+
     // The version is handled by #[command(version = "...")] or inferred from Cargo.toml
     // No explicit field is needed if we just want clap to print version and exit.
     // If we needed to *programmatically access* whether --version was passed (uncommon),
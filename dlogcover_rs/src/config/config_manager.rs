@@ -1,59 +1,516 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use crate::utils::file_utils;
 use crate::cli::CliOptions; // Import CliOptions
 use log::{error, warn, info, debug};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Which construct types `AstAnalyzer` records into `FileAstInfo`. Parsed from config/CLI
+/// strings (e.g. `"branches"`) via `FromStr`; `current_function_qname` tracking in the visitor
+/// still runs regardless of mode so branches/exceptions keep correct parent attribution even
+/// when `Functions` isn't selected.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalysisMode {
+    Functions,
+    Branches,
+    Exceptions,
+    All,
+}
+
+impl AnalysisMode {
+    pub fn includes_functions(&self) -> bool {
+        matches!(self, AnalysisMode::Functions | AnalysisMode::All)
+    }
+    pub fn includes_branches(&self) -> bool {
+        matches!(self, AnalysisMode::Branches | AnalysisMode::All)
+    }
+    pub fn includes_exceptions(&self) -> bool {
+        matches!(self, AnalysisMode::Exceptions | AnalysisMode::All)
+    }
+}
+
+impl FromStr for AnalysisMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "functions" => Ok(AnalysisMode::Functions),
+            "branches" => Ok(AnalysisMode::Branches),
+            "exceptions" => Ok(AnalysisMode::Exceptions),
+            "all" => Ok(AnalysisMode::All),
+            other => Err(format!(
+                "Unknown analysis mode '{}'; expected one of: functions, branches, exceptions, all",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for AnalysisMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            AnalysisMode::Functions => "functions",
+            AnalysisMode::Branches => "branches",
+            AnalysisMode::Exceptions => "exceptions",
+            AnalysisMode::All => "all",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Maximum depth of nested `include` directives before `load_config` gives up; guards against
+/// accidental or malicious runaway recursion even when no cycle is present.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+// Every section below implements `Default` with values matching the historical built-in defaults
+// and is annotated `#[serde(default)]` both where `Config` holds it (so an entirely absent section
+// falls back to `Default::default()`) and per-field (so a partially-specified section, e.g.
+// `{ "report": { "format": "html" } }`, fills in only the missing fields). This lets
+// `get_default_config` and JSON deserialization share one source of truth instead of
+// duplicating the defaults.
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Config {
+    #[serde(default)]
     pub scan: ScanConfig,
+    #[serde(default)]
     pub log_functions: LogFunctionsConfig,
+    #[serde(default)]
     pub analysis: AnalysisConfig,
+    #[serde(default)]
     pub report: ReportConfig,
+    #[serde(default)]
+    pub coverage_rules: CoverageRulesConfig,
+    /// Config for `core::coverage::suppression`'s pre-report normalization pass. See
+    /// `SuppressionConfig`.
+    #[serde(default)]
+    pub suppression: SuppressionConfig,
+    /// How `utils::log_utils::init_logger` sets up the application's own diagnostic logging
+    /// (distinct from `report`, which covers the generated coverage report). See `LoggingConfig`.
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    /// Regex include/exclude lists scoping which functions/files `AstAnalyzer` keeps. See
+    /// `AstAnalyzer::new` for how these are compiled and applied.
+    #[serde(default)]
+    pub filters: FiltersConfig,
+    /// Named overlays selectable with `--profile <name>`, e.g. a "ci" profile that sets
+    /// `analysis.mode` to `all` and reports JSON, or a "quick" profile that sets it to
+    /// `functions` for a fast function-only pass. See `ConfigManager::apply_profile`.
+    #[serde(default)]
+    pub profiles: HashMap<String, ProfilePatch>,
+    /// Other config files to deep-merge underneath this one, resolved relative to this file's
+    /// directory. See `ConfigManager::load_config` for merge semantics.
+    #[serde(default)]
+    pub include: Vec<String>,
+}
+
+/// Regex patterns scoping which functions/files `AstAnalyzer` analyzes. `exclude` is applied
+/// first; `include`, when non-empty, then requires a match for the item to be kept. Patterns are
+/// matched against absolute file paths (for files) or `qualified_name` (for functions), which is
+/// clang's USR for the function (e.g. `c:@N@MyNamespace@F@foo#`), so
+/// `"include": ["@N@MyNamespace@"]` focuses analysis on one namespace.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FiltersConfig {
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+/// A partial overlay over `ScanConfig`/`AnalysisConfig`/`ReportConfig`, applied by
+/// `ConfigManager::apply_profile`. Every field is optional: only the ones present in the
+/// selected profile override the base configuration, mirroring how `#[serde(default)]` lets a
+/// config file specify only the sections it cares about.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ProfilePatch {
+    #[serde(default)]
+    pub scan: ScanPatch,
+    #[serde(default)]
+    pub analysis: AnalysisPatch,
+    #[serde(default)]
+    pub report: ReportPatch,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ScanPatch {
+    pub directories: Option<Vec<String>>,
+    pub excludes: Option<Vec<String>>,
+    pub file_types: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AnalysisPatch {
+    pub mode: Option<AnalysisMode>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ReportPatch {
+    pub format: Option<String>,
+    pub timestamp_format: Option<String>,
+    pub min_overall_coverage: Option<f64>,
+    pub min_function_coverage: Option<f64>,
+    pub min_branch_coverage: Option<f64>,
+    pub min_exception_coverage: Option<f64>,
+    pub min_per_file_coverage: Option<f64>,
+    pub color: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ScanConfig {
+    #[serde(default = "ScanConfig::default_directories")]
     pub directories: Vec<String>,
+    #[serde(default = "ScanConfig::default_excludes")]
     pub excludes: Vec<String>,
+    #[serde(default = "ScanConfig::default_file_types")]
     pub file_types: Vec<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl ScanConfig {
+    fn default_directories() -> Vec<String> {
+        vec!["./".to_string()]
+    }
+    fn default_excludes() -> Vec<String> {
+        vec!["build/".to_string(), "test/".to_string()]
+    }
+    fn default_file_types() -> Vec<String> {
+        vec![".cpp".to_string(), ".cc".to_string(), ".cxx".to_string(), ".h".to_string(), ".hpp".to_string()]
+    }
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        ScanConfig {
+            directories: Self::default_directories(),
+            excludes: Self::default_excludes(),
+            file_types: Self::default_file_types(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct LogFunctionsConfig {
+    #[serde(default)]
     pub qt: QtLogConfig,
+    #[serde(default)]
     pub custom: CustomLogConfig,
+    /// Maps a function-like macro name (e.g. `"LOG_DEBUG"`) to the log level it represents, for
+    /// logging done through macros rather than a plain function/method call. See
+    /// `LogIdentifier`'s handling of `CXCursor_MacroExpansion`.
+    #[serde(default)]
+    pub macros: HashMap<String, String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct QtLogConfig {
+    #[serde(default = "QtLogConfig::default_enabled")]
     pub enabled: bool,
+    #[serde(default = "QtLogConfig::default_functions")]
     pub functions: Vec<String>,
+    #[serde(default = "QtLogConfig::default_category_functions")]
     pub category_functions: Vec<String>,
 }
 
+impl QtLogConfig {
+    fn default_enabled() -> bool {
+        true
+    }
+    fn default_functions() -> Vec<String> {
+        vec!["qDebug".to_string(), "qInfo".to_string(), "qWarning".to_string(), "qCritical".to_string(), "qFatal".to_string()]
+    }
+    fn default_category_functions() -> Vec<String> {
+        vec!["qCDebug".to_string(), "qCInfo".to_string(), "qCWarning".to_string(), "qCCritical".to_string()]
+    }
+}
+
+impl Default for QtLogConfig {
+    fn default() -> Self {
+        QtLogConfig {
+            enabled: Self::default_enabled(),
+            functions: Self::default_functions(),
+            category_functions: Self::default_category_functions(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CustomLogConfig {
+    #[serde(default = "CustomLogConfig::default_enabled")]
     pub enabled: bool,
+    #[serde(default = "CustomLogConfig::default_functions")]
     pub functions: HashMap<String, Vec<String>>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl CustomLogConfig {
+    fn default_enabled() -> bool {
+        true
+    }
+    fn default_functions() -> HashMap<String, Vec<String>> {
+        let mut map = HashMap::new();
+        map.insert("debug".to_string(), vec!["fmDebug".to_string()]);
+        map.insert("info".to_string(), vec!["fmInfo".to_string()]);
+        map.insert("warning".to_string(), vec!["fmWarning".to_string()]);
+        map.insert("critical".to_string(), vec!["fmCritical".to_string()]);
+        map
+    }
+}
+
+impl Default for CustomLogConfig {
+    fn default() -> Self {
+        CustomLogConfig {
+            enabled: Self::default_enabled(),
+            functions: Self::default_functions(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct AnalysisConfig {
-    pub function_coverage: bool,
-    pub branch_coverage: bool,
-    pub exception_coverage: bool,
-    pub key_path_coverage: bool,
+    /// Directory containing a clang `compile_commands.json`. When set, `AstAnalyzer` looks up
+    /// the exact per-file compile arguments recorded there instead of the hardcoded
+    /// `-std=c++17 -xc++` fallback, so include paths, defines, and per-file language standards
+    /// from the real build are honored.
+    #[serde(default)]
+    pub compile_commands_dir: Option<String>,
+    /// Which construct types to collect. Defaults to `All`; set to e.g. `Functions` to run a
+    /// fast function-inventory pass without paying for branch/exception traversal.
+    #[serde(default = "AnalysisConfig::default_mode")]
+    pub mode: AnalysisMode,
+    /// Caps how many files `AstAnalyzer` parses concurrently. `None` (the default) lets rayon's
+    /// global thread pool pick based on available cores; set this to bound memory when many
+    /// concurrent translation units would otherwise be held at once.
+    #[serde(default)]
+    pub max_threads: Option<usize>,
+    /// The minimum clang diagnostic severity that aborts parsing a file, one of `"error"`,
+    /// `"fatal"`, or `"never"`. Defaults to `"error"`, matching the historical behavior of
+    /// bailing out on any `Error`- or `Fatal`-severity diagnostic. `"fatal"` tolerates plain
+    /// errors (e.g. from an incomplete/best-effort compile_commands.json) and only bails on
+    /// `Fatal`; `"never"` always returns whatever log call sites were found regardless of
+    /// diagnostics.
+    #[serde(default = "AnalysisConfig::default_fatal_diagnostic_severity")]
+    pub fatal_diagnostic_severity: String,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl AnalysisConfig {
+    fn default_mode() -> AnalysisMode {
+        AnalysisMode::All
+    }
+    fn default_fatal_diagnostic_severity() -> String {
+        "error".to_string()
+    }
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        AnalysisConfig {
+            compile_commands_dir: None,
+            mode: Self::default_mode(),
+            max_threads: None,
+            fatal_diagnostic_severity: Self::default_fatal_diagnostic_severity(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ReportConfig {
-    pub format: String, 
+    #[serde(default = "ReportConfig::default_format")]
+    pub format: String,
+    #[serde(default = "ReportConfig::default_timestamp_format")]
     pub timestamp_format: String,
+    /// Minimum acceptable `project_overall.percentage`. `None` (the default) disables the
+    /// check. See `core::coverage::threshold::check_thresholds`.
+    #[serde(default)]
+    pub min_overall_coverage: Option<f64>,
+    /// Minimum acceptable `total_functions.percentage`.
+    #[serde(default)]
+    pub min_function_coverage: Option<f64>,
+    /// Minimum acceptable `total_branches.percentage`.
+    #[serde(default)]
+    pub min_branch_coverage: Option<f64>,
+    /// Minimum acceptable `total_exceptions.percentage`.
+    #[serde(default)]
+    pub min_exception_coverage: Option<f64>,
+    /// Like `min_overall_coverage`, but applied to each file's own `overall.percentage`
+    /// individually rather than only the project-wide rollup.
+    #[serde(default)]
+    pub min_per_file_coverage: Option<f64>,
+    /// Forces ANSI color on (`Some(true)`) or off (`Some(false)`) for `TextReporter`'s output.
+    /// `None` (the default) auto-detects based on whether stdout is a terminal.
+    #[serde(default)]
+    pub color: Option<bool>,
+}
+
+impl ReportConfig {
+    fn default_format() -> String {
+        "text".to_string()
+    }
+    fn default_timestamp_format() -> String {
+        "YYYYMMDD_HHMMSS".to_string()
+    }
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        ReportConfig {
+            format: Self::default_format(),
+            timestamp_format: Self::default_timestamp_format(),
+            min_overall_coverage: None,
+            min_function_coverage: None,
+            min_branch_coverage: None,
+            min_exception_coverage: None,
+            color: None,
+            min_per_file_coverage: None,
+        }
+    }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CoverageRulesConfig {
+    #[serde(default = "CoverageRulesConfig::default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "CoverageRulesConfig::default_min_function_lines")]
+    pub min_function_lines: usize,
+}
 
-#[allow(dead_code)] 
+impl CoverageRulesConfig {
+    fn default_enabled() -> bool {
+        true
+    }
+    fn default_min_function_lines() -> usize {
+        3
+    }
+}
+
+impl Default for CoverageRulesConfig {
+    fn default() -> Self {
+        CoverageRulesConfig {
+            enabled: Self::default_enabled(),
+            min_function_lines: Self::default_min_function_lines(),
+        }
+    }
+}
+
+/// Controls `core::coverage::suppression`, a pre-report pass that drops already-computed
+/// uncovered items matching these criteria from a [`ProjectCoverage`](crate::core::coverage::ProjectCoverage)
+/// (and its totals), rather than `coverage_rules`, which excludes items from the AST *before*
+/// coverage is calculated.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct SuppressionConfig {
+    /// Regexes matched against each uncovered item's qualified name (functions) or parent
+    /// function's qualified name (branches/exceptions).
+    #[serde(default)]
+    pub ignore_patterns: Vec<String>,
+    /// Attribute/macro names (e.g. `"test"`, `"derive"`) whose presence as a path segment of an
+    /// uncovered function's qualified name suppresses it. `FunctionInfo` doesn't carry a
+    /// separate attribute list, so macro-generated functions are recognized this way instead —
+    /// the same constraint the uncovered-only `CoverageMetrics` model imposes elsewhere.
+    #[serde(default)]
+    pub ignore_attributes: Vec<String>,
+    /// Exact qualified names to suppress, for one-off exceptions that don't fit a pattern.
+    #[serde(default)]
+    pub ignore_qualified_names: Vec<String>,
+}
+
+
+/// What `utils::log_utils::init_logger` does when a `LoggingConfig::File` path already exists.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IfExists {
+    /// Keep the existing contents and write new log lines after them.
+    Append,
+    /// Discard the existing contents and start the file empty.
+    Truncate,
+    /// Refuse to start up; `init_logger` returns an error.
+    Fail,
+}
+
+/// How the application's own diagnostic logging (as opposed to `ReportConfig`, which covers the
+/// generated coverage report) is set up. Consumed by `utils::log_utils::init_logger`, which the
+/// `--log-level` CLI flag overrides regardless of which variant is selected.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "mode", rename_all = "lowercase")]
+pub enum LoggingConfig {
+    StderrTerminal {
+        #[serde(default = "LoggingConfig::default_level")]
+        level: String,
+        /// How each log line is rendered. Only meaningful here and on `File`: `Journald`/`Syslog`
+        /// hand formatting off to their own backend.
+        #[serde(default)]
+        format: LogFormat,
+        /// Per-module filter directives layered on top of `level`, e.g.
+        /// `"dlogcover::core=debug,dlogcover::source_manager=warn"`. The most specific matching
+        /// module path wins; modules with no match fall back to `level`.
+        #[serde(default)]
+        filters: Option<String>,
+    },
+    File {
+        #[serde(default = "LoggingConfig::default_level")]
+        level: String,
+        path: String,
+        #[serde(default = "LoggingConfig::default_if_exists")]
+        if_exists: IfExists,
+        /// Rotate `path` once a write would push it past this size. `None` (the default)
+        /// disables rotation entirely.
+        #[serde(default)]
+        max_size_bytes: Option<u64>,
+        /// How many rotated backups (`path.1`, `path.2`, ...) to keep; older ones are deleted.
+        /// Ignored when `max_size_bytes` is `None`.
+        #[serde(default)]
+        max_backups: u32,
+        #[serde(default)]
+        format: LogFormat,
+        #[serde(default)]
+        filters: Option<String>,
+    },
+    Journald {
+        #[serde(default = "LoggingConfig::default_level")]
+        level: String,
+    },
+    /// Sends log records to the local syslog daemon over its Unix domain socket.
+    Syslog {
+        #[serde(default = "LoggingConfig::default_level")]
+        level: String,
+    },
+}
+
+/// How `utils::log_utils` renders each log line for `StderrTerminal`/`File` logging.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// The usual `env_logger`-style line (timestamp, colorized level, target, message).
+    Default,
+    /// One `key=value` pair per field on a single line, e.g. `level=INFO target=dlogcover msg="..."`.
+    Compact,
+}
+
+impl Default for LogFormat {
+    fn default() -> Self {
+        LogFormat::Default
+    }
+}
+
+impl LoggingConfig {
+    fn default_level() -> String {
+        "info".to_string()
+    }
+    fn default_if_exists() -> IfExists {
+        IfExists::Append
+    }
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        LoggingConfig::StderrTerminal {
+            level: Self::default_level(),
+            format: LogFormat::default(),
+            filters: None,
+        }
+    }
+}
+
+#[allow(dead_code)]
 pub struct ConfigManager {
     pub config: Config, 
 }
@@ -71,22 +528,45 @@ impl ConfigManager {
                 Self::load_config(path)?
             }
             None => {
-                info!("No config path from CLI. Trying default path './dlogcover.json'.");
-                match Self::load_config("./dlogcover.json") {
-                    Ok(cfg) => cfg,
-                    Err(_) => {
-                        warn!("Failed to load from default path './dlogcover.json'. Using built-in default configuration.");
+                info!("No config path from CLI. Discovering config files in the directory hierarchy.");
+                let start_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+                match Self::load_hierarchical_config(&start_dir) {
+                    Ok(Some(cfg)) => cfg,
+                    Ok(None) => {
+                        warn!("No config file found by walking up from '{}'. Using built-in default configuration.", start_dir.display());
                         Self::get_default_config()
                     }
+                    Err(e) => {
+                        error!("Hierarchical config discovery failed: {}", e);
+                        return Err(e.into());
+                    }
                 }
             }
         };
 
-        // 2. Override with CLI options
+        // 2. Apply a named profile, if one was selected (after file load, before everything else
+        // so CLI flags and env overrides still take precedence over it).
+        if let Some(profile_name) = &cli_options.profile {
+            match config.profiles.get(profile_name).cloned() {
+                Some(patch) => {
+                    info!("Applying analysis profile '{}'.", profile_name);
+                    Self::apply_profile(&mut config, &patch);
+                }
+                None => {
+                    debug!("Profile '{}' is not defined; deferring to validation to report the error.", profile_name);
+                }
+            }
+        }
+
+        // 3. Override with environment variables (between file config and CLI flags in priority)
+        Self::apply_env_overrides(&mut config);
+
+        // 4. Override with CLI options
         Self::merge_cli_options(&mut config, cli_options);
 
-        // 3. Validate the final merged configuration
-        if let Err(validation_errors) = Self::validate_config(&config) {
+        // 5. Validate the final merged configuration, including that `--profile` (if given)
+        // named a profile that actually exists.
+        if let Err(validation_errors) = Self::validate_config(&config, cli_options.profile.as_deref()) {
             error!("Final configuration validation failed:");
             for err in &validation_errors {
                 error!("- {}", err);
@@ -103,6 +583,130 @@ impl ConfigManager {
         Ok(ConfigManager { config })
     }
 
+    /// Applies `DLOGCOVER_*` environment variable overrides onto `config`. Priority is
+    /// CLI flags > environment variables > config file > built-in defaults, so this runs after
+    /// `load_config`/`get_default_config` but before `merge_cli_options`. Variable names are the
+    /// dotted config path, uppercased with `.` replaced by `_` (e.g. `report.format` ->
+    /// `DLOGCOVER_REPORT_FORMAT`); list fields accept comma-separated values and bool fields
+    /// accept `true`/`false`.
+    fn apply_env_overrides(config: &mut Config) {
+        info!("Applying environment variable overrides (if any)...");
+
+        Self::env_string_list("DLOGCOVER_SCAN_DIRECTORIES", &mut config.scan.directories);
+        Self::env_string_list("DLOGCOVER_SCAN_EXCLUDES", &mut config.scan.excludes);
+        Self::env_string_list("DLOGCOVER_SCAN_FILE_TYPES", &mut config.scan.file_types);
+
+        Self::env_bool("DLOGCOVER_LOG_FUNCTIONS_QT_ENABLED", &mut config.log_functions.qt.enabled);
+        Self::env_string_list("DLOGCOVER_LOG_FUNCTIONS_QT_FUNCTIONS", &mut config.log_functions.qt.functions);
+        Self::env_bool("DLOGCOVER_LOG_FUNCTIONS_CUSTOM_ENABLED", &mut config.log_functions.custom.enabled);
+
+        if let Ok(val) = std::env::var("DLOGCOVER_ANALYSIS_MODE") {
+            match AnalysisMode::from_str(&val) {
+                Ok(mode) => {
+                    info!("Env override: DLOGCOVER_ANALYSIS_MODE -> {}", mode);
+                    config.analysis.mode = mode;
+                }
+                Err(e) => warn!("Env override DLOGCOVER_ANALYSIS_MODE has invalid value '{}': {}. Ignoring.", val, e),
+            }
+        }
+
+        Self::env_string("DLOGCOVER_REPORT_FORMAT", &mut config.report.format);
+        Self::env_string("DLOGCOVER_REPORT_TIMESTAMP_FORMAT", &mut config.report.timestamp_format);
+
+        Self::env_bool("DLOGCOVER_COVERAGE_RULES_ENABLED", &mut config.coverage_rules.enabled);
+        Self::env_usize("DLOGCOVER_COVERAGE_RULES_MIN_FUNCTION_LINES", &mut config.coverage_rules.min_function_lines);
+
+        Self::env_string_list("DLOGCOVER_SUPPRESSION_IGNORE_PATTERNS", &mut config.suppression.ignore_patterns);
+        Self::env_string_list("DLOGCOVER_SUPPRESSION_IGNORE_ATTRIBUTES", &mut config.suppression.ignore_attributes);
+        Self::env_string_list("DLOGCOVER_SUPPRESSION_IGNORE_QUALIFIED_NAMES", &mut config.suppression.ignore_qualified_names);
+    }
+
+    fn env_string(var_name: &str, target: &mut String) {
+        if let Ok(val) = std::env::var(var_name) {
+            info!("Env override: {} -> '{}'", var_name, val);
+            *target = val;
+        }
+    }
+
+    fn env_string_list(var_name: &str, target: &mut Vec<String>) {
+        if let Ok(val) = std::env::var(var_name) {
+            let parsed: Vec<String> = val.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect();
+            info!("Env override: {} -> {:?}", var_name, parsed);
+            *target = parsed;
+        }
+    }
+
+    fn env_bool(var_name: &str, target: &mut bool) {
+        if let Ok(val) = std::env::var(var_name) {
+            match val.trim().to_lowercase().as_str() {
+                "true" => {
+                    info!("Env override: {} -> true", var_name);
+                    *target = true;
+                }
+                "false" => {
+                    info!("Env override: {} -> false", var_name);
+                    *target = false;
+                }
+                other => warn!("Env override {} has invalid bool value '{}'; expected 'true' or 'false'. Ignoring.", var_name, other),
+            }
+        }
+    }
+
+    fn env_usize(var_name: &str, target: &mut usize) {
+        if let Ok(val) = std::env::var(var_name) {
+            match val.trim().parse::<usize>() {
+                Ok(parsed) => {
+                    info!("Env override: {} -> {}", var_name, parsed);
+                    *target = parsed;
+                }
+                Err(e) => warn!("Env override {} has invalid integer value '{}': {}. Ignoring.", var_name, val, e),
+            }
+        }
+    }
+
+    /// Overlays a `ProfilePatch` onto `config`, applying only the fields the profile actually
+    /// sets and leaving everything else untouched.
+    fn apply_profile(config: &mut Config, patch: &ProfilePatch) {
+        if let Some(directories) = &patch.scan.directories {
+            config.scan.directories = directories.clone();
+        }
+        if let Some(excludes) = &patch.scan.excludes {
+            config.scan.excludes = excludes.clone();
+        }
+        if let Some(file_types) = &patch.scan.file_types {
+            config.scan.file_types = file_types.clone();
+        }
+
+        if let Some(mode) = patch.analysis.mode {
+            config.analysis.mode = mode;
+        }
+
+        if let Some(format) = &patch.report.format {
+            config.report.format = format.clone();
+        }
+        if let Some(timestamp_format) = &patch.report.timestamp_format {
+            config.report.timestamp_format = timestamp_format.clone();
+        }
+        if let Some(v) = patch.report.min_overall_coverage {
+            config.report.min_overall_coverage = Some(v);
+        }
+        if let Some(v) = patch.report.min_function_coverage {
+            config.report.min_function_coverage = Some(v);
+        }
+        if let Some(v) = patch.report.min_branch_coverage {
+            config.report.min_branch_coverage = Some(v);
+        }
+        if let Some(v) = patch.report.min_exception_coverage {
+            config.report.min_exception_coverage = Some(v);
+        }
+        if let Some(v) = patch.report.min_per_file_coverage {
+            config.report.min_per_file_coverage = Some(v);
+        }
+        if let Some(v) = patch.report.color {
+            config.report.color = Some(v);
+        }
+    }
+
     fn merge_cli_options(config: &mut Config, cli_options: &CliOptions) {
         info!("Merging CLI options into configuration...");
 
@@ -141,74 +745,338 @@ impl ConfigManager {
             info!("Overriding report format with CLI option: {}", format);
             config.report.format = format.clone();
         }
+
+        if let Some(mode_str) = &cli_options.mode {
+            match AnalysisMode::from_str(mode_str) {
+                Ok(mode) => {
+                    info!("Overriding analysis mode with CLI option: {}", mode);
+                    config.analysis.mode = mode;
+                }
+                Err(e) => warn!("Ignoring invalid --mode value: {}", e),
+            }
+        }
         debug!("Configuration after merging CLI options: {:?}", config);
     }
 
-    // load_config remains largely the same
+    /// Base name (without extension) of a config file discoverable by the directory walk.
+    const CONFIG_BASE_NAME: &'static str = "dlogcover";
+    /// Extensions recognized as config files, dispatched to a format-specific parser by
+    /// `parse_config_str`. Also used for ambiguity detection: a `dlogcover.json5` sitting next to
+    /// a `dlogcover.json` is flagged rather than one being silently ignored.
+    const SUPPORTED_CONFIG_EXTENSIONS: &'static [&'static str] = &["json", "json5", "toml"];
+
+    /// Looks for a `dlogcover.<ext>` file directly inside `dir`. Returns an error naming every
+    /// match if more than one supported extension is present at once (e.g. `dlogcover.json` and
+    /// `dlogcover.json5` coexisting), since silently picking one would hide a configuration
+    /// mistake.
+    fn find_config_in_dir(dir: &Path) -> Result<Option<PathBuf>, String> {
+        let mut found: Vec<PathBuf> = Self::SUPPORTED_CONFIG_EXTENSIONS
+            .iter()
+            .map(|ext| dir.join(format!("{}.{}", Self::CONFIG_BASE_NAME, ext)))
+            .filter(|candidate| candidate.is_file())
+            .collect();
+
+        match found.len() {
+            0 => Ok(None),
+            1 => Ok(Some(found.remove(0))),
+            _ => Err(format!(
+                "Ambiguous configuration in '{}': found {}; keep only one",
+                dir.display(),
+                found.iter().map(|p| format!("'{}'", p.display())).collect::<Vec<_>>().join(", ")
+            )),
+        }
+    }
+
+    /// Walks from `start_dir` up to the filesystem root (and, if `$HOME` differs from every
+    /// directory already visited, the user's home directory) collecting one `dlogcover.*` file
+    /// per directory, the way Cargo layers `.cargo/config` files. Files closer to `start_dir`
+    /// take precedence: the walk merges root-most first, then deep-merges each closer file on
+    /// top via `deep_merge`, exactly as `include` directives do. Returns `Ok(None)` if no config
+    /// file is found anywhere in the hierarchy.
+    pub fn load_hierarchical_config(start_dir: &Path) -> Result<Option<Config>, String> {
+        let mut dirs = Vec::new();
+        let mut current = Some(start_dir.to_path_buf());
+        while let Some(dir) = current {
+            dirs.push(dir.clone());
+            current = dir.parent().map(|p| p.to_path_buf());
+        }
+        if let Some(home) = std::env::var_os("HOME").map(PathBuf::from) {
+            if !dirs.contains(&home) {
+                dirs.push(home);
+            }
+        }
+
+        // `dirs` is ordered closest-to-`start_dir` first; collect configs in that order, then
+        // fold from the far end inward so farther-away files act as the base and closer files
+        // override them.
+        let mut configs_closest_first = Vec::new();
+        for dir in &dirs {
+            if let Some(path) = Self::find_config_in_dir(dir)? {
+                debug!("Found config file while walking directory hierarchy: {}", path.display());
+                let path_str = path.to_string_lossy().into_owned();
+                let config = Self::load_config(&path_str).map_err(|e| e.to_string())?;
+                configs_closest_first.push(config);
+            }
+        }
+
+        let mut merged: Option<Config> = None;
+        for config in configs_closest_first.into_iter().rev() {
+            merged = Some(match merged {
+                Some(base) => Self::deep_merge(base, config),
+                None => config,
+            });
+        }
+        Ok(merged)
+    }
+
+    /// Parses `content` into a `Config`, picking the format from `path`'s extension: `.json5`
+    /// uses the json5 parser (comments, trailing commas, unquoted keys — handy for annotating
+    /// scan excludes and custom log-function maps inline), `.toml` uses the toml parser, and
+    /// anything else (including plain `.json`) falls back to strict JSON. The in-memory `Config`
+    /// struct and everything downstream of parsing (merging, env overrides, validation) is the
+    /// same regardless of source format.
+    fn parse_config_str(content: &str, path: &Path) -> Result<Config, Box<dyn std::error::Error>> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json5") => Ok(json5::from_str(content)?),
+            Some("toml") => Ok(toml::from_str(content)?),
+            _ => Ok(serde_json::from_str(content)?),
+        }
+    }
+
+    // load_config now resolves `include` directives, deep-merging included files underneath
+    // the requested one (so the requested file wins on conflicts).
     pub fn load_config(path: &str) -> Result<Config, Box<dyn std::error::Error>> {
+        let mut visited = HashSet::new();
+        Self::load_config_recursive(path, &mut visited, 0)
+    }
+
+    fn load_config_recursive(
+        path: &str,
+        visited: &mut HashSet<PathBuf>,
+        depth: usize,
+    ) -> Result<Config, Box<dyn std::error::Error>> {
+        if depth > MAX_INCLUDE_DEPTH {
+            return Err(format!(
+                "Config include depth exceeded {} while loading '{}'; check for a cycle",
+                MAX_INCLUDE_DEPTH, path
+            )
+            .into());
+        }
+
         info!("Loading configuration from: {}", path);
+        let canonical_path = file_utils::to_absolute_path(path)
+            .map_err(|e| format!("Failed to resolve path '{}': {}", path, e))?;
+
+        if !visited.insert(canonical_path.clone()) {
+            return Err(format!(
+                "Config include cycle detected: '{}' is included more than once",
+                canonical_path.display()
+            )
+            .into());
+        }
+
         let content = file_utils::read_file(path)
             .map_err(|e| {
                 error!("Failed to read config file '{}': {}", path, e);
                 e
             })?;
-        
-        let config: Config = serde_json::from_str(&content)
+
+        let config: Config = Self::parse_config_str(&content, &canonical_path)
             .map_err(|e| {
-                error!("Failed to parse JSON from config file '{}': {}", path, e);
+                error!("Failed to parse config file '{}': {}", path, e);
                 e
             })?;
-        
+
         info!("Configuration loaded successfully from {}", path);
-        Ok(config)
+
+        if config.include.is_empty() {
+            return Ok(config);
+        }
+
+        let base_dir: PathBuf = canonical_path.parent().unwrap_or_else(|| Path::new(".")).to_path_buf();
+        let mut merged_includes: Option<Config> = None;
+        for include_rel_path in &config.include {
+            let resolved = base_dir.join(include_rel_path);
+            let resolved_str = resolved.to_string_lossy().into_owned();
+            debug!("Resolving include '{}' -> '{}'", include_rel_path, resolved_str);
+            let included_config = Self::load_config_recursive(&resolved_str, visited, depth + 1)?;
+            merged_includes = Some(match merged_includes {
+                Some(acc) => Self::deep_merge(acc, included_config),
+                None => included_config,
+            });
+        }
+
+        let merged = match merged_includes {
+            Some(base) => Self::deep_merge(base, config),
+            None => config,
+        };
+        Ok(merged)
     }
 
-    // get_default_config remains the same
-    pub fn get_default_config() -> Config {
-        info!("Using default configuration.");
+    /// Merges a field that carries its own "unset" value (`default`): `overlay` wins only when it
+    /// differs from `default`, since a config file that doesn't mention the field deserializes to
+    /// `default` via `#[serde(default)]` and is indistinguishable from one that restates it
+    /// explicitly. This is the field-by-field analog of `ProfilePatch`'s `Option<T>` overlay, for
+    /// sections that can't be restructured into patches without breaking normal deserialization.
+    fn merge_scalar<T: PartialEq>(base: T, overlay: T, default: &T) -> T {
+        if overlay == *default {
+            base
+        } else {
+            overlay
+        }
+    }
+
+    fn merge_analysis(base: AnalysisConfig, overlay: AnalysisConfig) -> AnalysisConfig {
+        let default = AnalysisConfig::default();
+        AnalysisConfig {
+            compile_commands_dir: overlay.compile_commands_dir.or(base.compile_commands_dir),
+            mode: Self::merge_scalar(base.mode, overlay.mode, &default.mode),
+            max_threads: overlay.max_threads.or(base.max_threads),
+            fatal_diagnostic_severity: Self::merge_scalar(
+                base.fatal_diagnostic_severity,
+                overlay.fatal_diagnostic_severity,
+                &default.fatal_diagnostic_severity,
+            ),
+        }
+    }
+
+    fn merge_report(base: ReportConfig, overlay: ReportConfig) -> ReportConfig {
+        let default = ReportConfig::default();
+        ReportConfig {
+            format: Self::merge_scalar(base.format, overlay.format, &default.format),
+            timestamp_format: Self::merge_scalar(base.timestamp_format, overlay.timestamp_format, &default.timestamp_format),
+            min_overall_coverage: overlay.min_overall_coverage.or(base.min_overall_coverage),
+            min_function_coverage: overlay.min_function_coverage.or(base.min_function_coverage),
+            min_branch_coverage: overlay.min_branch_coverage.or(base.min_branch_coverage),
+            min_exception_coverage: overlay.min_exception_coverage.or(base.min_exception_coverage),
+            min_per_file_coverage: overlay.min_per_file_coverage.or(base.min_per_file_coverage),
+            color: overlay.color.or(base.color),
+        }
+    }
+
+    fn merge_coverage_rules(base: CoverageRulesConfig, overlay: CoverageRulesConfig) -> CoverageRulesConfig {
+        let default = CoverageRulesConfig::default();
+        CoverageRulesConfig {
+            enabled: Self::merge_scalar(base.enabled, overlay.enabled, &default.enabled),
+            min_function_lines: Self::merge_scalar(base.min_function_lines, overlay.min_function_lines, &default.min_function_lines),
+        }
+    }
+
+    fn merge_suppression(base: SuppressionConfig, overlay: SuppressionConfig) -> SuppressionConfig {
+        SuppressionConfig {
+            ignore_patterns: Self::concat_dedup(base.ignore_patterns, overlay.ignore_patterns),
+            ignore_attributes: Self::concat_dedup(base.ignore_attributes, overlay.ignore_attributes),
+            ignore_qualified_names: Self::concat_dedup(base.ignore_qualified_names, overlay.ignore_qualified_names),
+        }
+    }
+
+    /// `LoggingConfig` is a tagged enum rather than a flat struct, so unlike `merge_analysis` et
+    /// al. it can't be merged field-by-field across differing variants; `overlay` replaces `base`
+    /// wholesale, but only when it differs from `LoggingConfig::default()` (i.e. was actually
+    /// restated), so an overlay/include file that never mentions `logging` doesn't clobber a base
+    /// file's real setting.
+    fn merge_logging(base: LoggingConfig, overlay: LoggingConfig) -> LoggingConfig {
+        let default = LoggingConfig::default();
+        Self::merge_scalar(base, overlay, &default)
+    }
+
+    /// Deep-merges `overlay` on top of `base`: scalar fields are last-writer-wins (`overlay`
+    /// takes priority), list fields that name collections of functions/directories/excludes are
+    /// concatenated and deduplicated, `custom.functions` is merged key-by-key with the same
+    /// concatenate-and-dedup rule applied per level, and `analysis`/`report`/`coverage_rules`/
+    /// `suppression` are merged field-by-field (see `merge_analysis` and friends) rather than
+    /// replaced wholesale, so `include` and hierarchical config discovery still work when an
+    /// outer file only restates some of a section's fields. `logging` is the exception: see
+    /// `merge_logging`, which replaces it wholesale when the overlay restates it.
+    fn deep_merge(base: Config, overlay: Config) -> Config {
+        let mut custom_functions = base.log_functions.custom.functions;
+        for (level, funcs) in overlay.log_functions.custom.functions {
+            let entry = custom_functions.entry(level).or_default();
+            *entry = Self::concat_dedup(std::mem::take(entry), funcs);
+        }
+
+        let mut profiles = base.profiles;
+        for (name, patch) in overlay.profiles {
+            profiles.insert(name, patch);
+        }
+
+        let mut macros = base.log_functions.macros;
+        for (name, level) in overlay.log_functions.macros {
+            macros.insert(name, level);
+        }
+
         Config {
             scan: ScanConfig {
-                directories: vec!["./".to_string()],
-                excludes: vec!["build/".to_string(), "test/".to_string()],
-                file_types: vec![".cpp".to_string(), ".cc".to_string(), ".cxx".to_string(), ".h".to_string(), ".hpp".to_string()],
+                directories: Self::concat_dedup(base.scan.directories, overlay.scan.directories),
+                excludes: Self::concat_dedup(base.scan.excludes, overlay.scan.excludes),
+                file_types: Self::concat_dedup(base.scan.file_types, overlay.scan.file_types),
             },
             log_functions: LogFunctionsConfig {
                 qt: QtLogConfig {
-                    enabled: true,
-                    functions: vec!["qDebug".to_string(), "qInfo".to_string(), "qWarning".to_string(), "qCritical".to_string(), "qFatal".to_string()],
-                    category_functions: vec!["qCDebug".to_string(), "qCInfo".to_string(), "qCWarning".to_string(), "qCCritical".to_string()],
+                    enabled: overlay.log_functions.qt.enabled,
+                    functions: Self::concat_dedup(base.log_functions.qt.functions, overlay.log_functions.qt.functions),
+                    category_functions: Self::concat_dedup(
+                        base.log_functions.qt.category_functions,
+                        overlay.log_functions.qt.category_functions,
+                    ),
                 },
                 custom: CustomLogConfig {
-                    enabled: true,
-                    functions: {
-                        let mut map = HashMap::new();
-                        map.insert("debug".to_string(), vec!["fmDebug".to_string()]);
-                        map.insert("info".to_string(), vec!["fmInfo".to_string()]);
-                        map.insert("warning".to_string(), vec!["fmWarning".to_string()]);
-                        map.insert("critical".to_string(), vec!["fmCritical".to_string()]);
-                        map
-                    },
+                    enabled: overlay.log_functions.custom.enabled,
+                    functions: custom_functions,
                 },
+                macros,
             },
-            analysis: AnalysisConfig {
-                function_coverage: true,
-                branch_coverage: true,
-                exception_coverage: true,
-                key_path_coverage: true,
-            },
-            report: ReportConfig {
-                format: "text".to_string(),
-                timestamp_format: "YYYYMMDD_HHMMSS".to_string(),
-                // output_path: None, // If we add output_path to ReportConfig
+            analysis: Self::merge_analysis(base.analysis, overlay.analysis),
+            report: Self::merge_report(base.report, overlay.report),
+            logging: Self::merge_logging(base.logging, overlay.logging),
+            coverage_rules: Self::merge_coverage_rules(base.coverage_rules, overlay.coverage_rules),
+            suppression: Self::merge_suppression(base.suppression, overlay.suppression),
+            filters: FiltersConfig {
+                include: Self::concat_dedup(base.filters.include, overlay.filters.include),
+                exclude: Self::concat_dedup(base.filters.exclude, overlay.filters.exclude),
             },
+            profiles,
+            include: Vec::new(),
         }
     }
 
-    // validate_config remains the same
-    pub fn validate_config(config: &Config) -> Result<(), Vec<String>> {
+    /// Concatenates `a` then `b`, removing later duplicates while preserving first-seen order.
+    fn concat_dedup(a: Vec<String>, b: Vec<String>) -> Vec<String> {
+        let mut seen = HashSet::new();
+        let mut result = Vec::with_capacity(a.len() + b.len());
+        for item in a.into_iter().chain(b) {
+            if seen.insert(item.clone()) {
+                result.push(item);
+            }
+        }
+        result
+    }
+
+    /// Built-in fallback configuration, used when no config file is found. Shares its values
+    /// with JSON deserialization's per-field defaults via each section's `Default` impl, so
+    /// there's a single source of truth for what "default" means.
+    pub fn get_default_config() -> Config {
+        info!("Using default configuration.");
+        Config::default()
+    }
+
+    /// Validates the final merged configuration. `requested_profile` is the `--profile` name (if
+    /// any) so an unknown profile is reported as a validation error rather than silently
+    /// ignored.
+    pub fn validate_config(config: &Config, requested_profile: Option<&str>) -> Result<(), Vec<String>> {
         let mut errors = Vec::new();
         info!("Validating configuration...");
 
+        if let Some(profile_name) = requested_profile {
+            if !config.profiles.contains_key(profile_name) {
+                errors.push(format!(
+                    "Profile '{}' is not defined in the loaded configuration. Known profiles: {:?}",
+                    profile_name,
+                    config.profiles.keys().collect::<Vec<_>>()
+                ));
+            }
+        }
+
         if config.scan.directories.is_empty() {
             errors.push("Scan directories list cannot be empty.".to_string());
         }
@@ -246,7 +1114,7 @@ impl ConfigManager {
             warn!("Both Qt and Custom logging are disabled. No log functions will be scanned.");
         }
 
-        let allowed_formats = ["text", "html", "json"]; 
+        let allowed_formats = ["text", "html", "json", "lcov", "summary", "cobertura"];
         if !allowed_formats.contains(&config.report.format.to_lowercase().as_str()) {
             errors.push(format!("Invalid report format: '{}'. Allowed formats are: {:?}", config.report.format, allowed_formats));
         }
@@ -254,6 +1122,33 @@ impl ConfigManager {
             errors.push("Report timestamp_format cannot be empty.".to_string());
         }
 
+        let allowed_fatal_severities = ["error", "fatal", "never"];
+        if !allowed_fatal_severities.contains(&config.analysis.fatal_diagnostic_severity.to_lowercase().as_str()) {
+            errors.push(format!(
+                "Invalid analysis.fatal_diagnostic_severity: '{}'. Allowed values are: {:?}",
+                config.analysis.fatal_diagnostic_severity, allowed_fatal_severities
+            ));
+        }
+
+        if let LoggingConfig::File { path, max_size_bytes, .. } = &config.logging {
+            if path.trim().is_empty() {
+                errors.push("logging.path cannot be empty when logging.mode is \"file\".".to_string());
+            }
+            if *max_size_bytes == Some(0) {
+                errors.push("logging.max_size_bytes must be greater than zero when set.".to_string());
+            }
+        }
+
+        let logging_filters = match &config.logging {
+            LoggingConfig::StderrTerminal { filters, .. } | LoggingConfig::File { filters, .. } => filters.as_deref(),
+            LoggingConfig::Journald { .. } | LoggingConfig::Syslog { .. } => None,
+        };
+        if let Some(filters) = logging_filters {
+            if filters.trim().is_empty() {
+                errors.push("logging.filters cannot be an empty string; omit the field instead.".to_string());
+            }
+        }
+
         if errors.is_empty() {
             info!("Configuration validation successful.");
             Ok(())
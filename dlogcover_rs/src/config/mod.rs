@@ -8,5 +8,13 @@ pub use config_manager::{
     QtLogConfig,
     CustomLogConfig,
     AnalysisConfig,
-    ReportConfig
+    AnalysisMode,
+    ReportConfig,
+    CoverageRulesConfig,
+    SuppressionConfig,
+    FiltersConfig,
+    ProfilePatch,
+    LoggingConfig,
+    IfExists,
+    LogFormat,
 };
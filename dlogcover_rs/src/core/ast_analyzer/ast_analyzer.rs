@@ -3,15 +3,17 @@ use std::collections::HashMap;
 use std::ffi::{CString, CStr};
 use std::os::raw::c_char;
 
-use crate::config::Config;
+use crate::config::{AnalysisMode, Config};
 use crate::source_manager::SourceFile;
 use clang_sys::*;
 use log::{debug, error, info, warn};
-use serde::Serialize; // Added Serialize
+use rayon::prelude::*;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 
 // --- AST Information Structs ---
 
-#[derive(Debug, Clone, Serialize)] // Added Serialize
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct SourceLocation {
     pub file_path: PathBuf,
@@ -19,7 +21,7 @@ pub struct SourceLocation {
     pub column: u32,
 }
 
-#[derive(Debug, Clone, Serialize)] // Added Serialize
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct FunctionInfo {
     pub name: String,
@@ -33,7 +35,7 @@ pub struct FunctionInfo {
     pub has_body: bool,         
 }
 
-#[derive(Debug, Clone, Serialize)] // Added Serialize
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct BranchInfo {
     pub parent_function_qualified_name: String, 
@@ -43,7 +45,7 @@ pub struct BranchInfo {
     pub condition_expression: Option<String>, 
 }
 
-#[derive(Debug, Clone, Serialize)] // Added Serialize
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct ExceptionInfo {
     pub parent_function_qualified_name: String, 
@@ -53,7 +55,7 @@ pub struct ExceptionInfo {
     pub end_location: SourceLocation,
 }
 
-#[derive(Debug, Clone, Serialize)] // Added Serialize
+#[derive(Debug, Clone, Serialize)]
 #[allow(dead_code)]
 pub struct FileAstInfo {
     pub file_path: PathBuf, 
@@ -79,8 +81,28 @@ impl FileAstInfo {
 pub struct AstAnalyzer<'a> {
     config: &'a Config,
     analysis_results: HashMap<PathBuf, FileAstInfo>,
+    /// Loaded once from `config.analysis.compile_commands_dir`, if set, and reused for every
+    /// file so `compile_commands.json` is only parsed a single time per run.
+    compilation_database: Option<CXCompilationDatabase>,
+    include_filters: Vec<Regex>,
+    exclude_filters: Vec<Regex>,
 }
 
+impl<'a> Drop for AstAnalyzer<'a> {
+    fn drop(&mut self) {
+        if let Some(db) = self.compilation_database {
+            unsafe { clang_CompilationDatabase_dispose(db) };
+        }
+    }
+}
+
+// Safety: `parse_and_visit_file` creates its own `clang_createIndex`/`CXTranslationUnit` per
+// call, so no libclang parsing state is ever shared across threads. The only shared state is
+// `compilation_database`, accessed exclusively through libclang's read-only
+// `clang_CompilationDatabase_get*` query functions (safe to call concurrently), and
+// `include_filters`/`exclude_filters`, which are immutable after `new`.
+unsafe impl<'a> Sync for AstAnalyzer<'a> {}
+
 // --- Helper Functions ---
 #[allow(dead_code)]
 fn cxstring_to_string(cx_string: CXString) -> String {
@@ -115,41 +137,184 @@ fn get_source_location_from_clang(cx_location: CXSourceLocation) -> SourceLocati
 #[allow(dead_code)]
 struct VisitorContext<'v_data> {
     ast_info: &'v_data mut FileAstInfo,
-    current_file_path: &'v_data Path, 
-    current_function_qname: Option<String>, 
-    current_class_name: Option<String>,  
+    current_file_path: &'v_data Path,
+    current_function_qname: Option<String>,
+    current_class_name: Option<String>,
+    tu: CXTranslationUnit,
+    include_filters: &'v_data [Regex],
+    exclude_filters: &'v_data [Regex],
+    mode: AnalysisMode,
+}
+
+/// Free-function twin of `AstAnalyzer::passes_filters`, usable from the `extern "C"` visitor
+/// callback where only borrowed filter slices (not the analyzer itself) are reachable.
+fn passes_filters(value: &str, include_filters: &[Regex], exclude_filters: &[Regex]) -> bool {
+    if exclude_filters.iter().any(|re| re.is_match(value)) {
+        return false;
+    }
+    include_filters.is_empty() || include_filters.iter().any(|re| re.is_match(value))
 }
 
 
 // --- AstAnalyzer Implementation ---
 #[allow(dead_code)]
 impl<'a> AstAnalyzer<'a> {
-    pub fn new(config: &'a Config) -> Self {
+    pub fn new(config: &'a Config) -> Result<Self, String> {
         debug!("Initializing AstAnalyzer...");
-        AstAnalyzer {
+
+        let include_filters = Self::compile_filters(&config.filters.include)?;
+        let exclude_filters = Self::compile_filters(&config.filters.exclude)?;
+
+        let compilation_database = config.analysis.compile_commands_dir.as_ref().and_then(|dir| {
+            let c_dir = CString::new(dir.as_bytes()).ok()?;
+            let mut error = CXCompilationDatabase_NoError;
+            let db = unsafe { clang_CompilationDatabase_fromDirectory(c_dir.as_ptr(), &mut error) };
+            if error != CXCompilationDatabase_NoError || db.is_null() {
+                warn!("Failed to load compile_commands.json from '{}'; falling back to default compile args for every file.", dir);
+                None
+            } else {
+                info!("Loaded compilation database from '{}'.", dir);
+                Some(db)
+            }
+        });
+
+        Ok(AstAnalyzer {
             config,
             analysis_results: HashMap::new(),
+            compilation_database,
+            include_filters,
+            exclude_filters,
+        })
+    }
+
+    /// Compiles each pattern in `patterns` with the `regex` crate, reporting a readable error
+    /// (naming the offending pattern) the first time one fails to compile.
+    fn compile_filters(patterns: &[String]) -> Result<Vec<Regex>, String> {
+        patterns
+            .iter()
+            .map(|pattern| Regex::new(pattern).map_err(|e| format!("failed to parse filter /{}/: {}", pattern, e)))
+            .collect()
+    }
+
+    /// Whether `value` survives the configured include/exclude filters: excluded first, then,
+    /// if any include patterns are configured, `value` must match at least one of them.
+    fn passes_filters(&self, value: &str) -> bool {
+        passes_filters(value, &self.include_filters, &self.exclude_filters)
+    }
+
+    /// Looks up the exact compile argument vector clang recorded for `file_path` in the loaded
+    /// compilation database, stripping the positional source path and output flags (`-o`, `-c`)
+    /// since those are supplied separately to `clang_parseTranslationUnit`. Returns `None` if no
+    /// database is loaded or no entry exists for this file, in which case the caller falls back
+    /// to the hardcoded default args.
+    fn compile_args_for_file(&self, file_path: &Path) -> Option<Vec<CString>> {
+        let db = self.compilation_database?;
+        let c_file_path = CString::new(file_path.to_string_lossy().as_bytes()).ok()?;
+
+        unsafe {
+            let commands = clang_CompilationDatabase_getCompileCommands(db, c_file_path.as_ptr());
+            if commands.is_null() {
+                return None;
+            }
+            let num_commands = clang_CompileCommands_getSize(commands);
+            if num_commands == 0 {
+                clang_CompileCommands_dispose(commands);
+                return None;
+            }
+
+            let command = clang_CompileCommands_getCommand(commands, 0);
+            let num_args = clang_CompileCommand_getNumArgs(command);
+            let file_path_str = file_path.to_string_lossy().into_owned();
+
+            let mut args = Vec::new();
+            let mut skip_next = false;
+            // Skip index 0: it's the compiler executable itself (e.g. "clang++"), not a flag.
+            for i in 1..num_args {
+                let arg = cxstring_to_string(clang_CompileCommand_getArg(command, i as u32));
+                if skip_next {
+                    skip_next = false;
+                    continue;
+                }
+                if arg == "-o" || arg == "-c" {
+                    skip_next = arg == "-o";
+                    continue;
+                }
+                if arg == file_path_str {
+                    continue;
+                }
+                args.push(arg);
+            }
+
+            clang_CompileCommands_dispose(commands);
+
+            if args.is_empty() {
+                return None;
+            }
+            Some(args.into_iter().filter_map(|a| CString::new(a).ok()).collect())
         }
     }
 
+    /// Parses every (non-filtered-out) file concurrently, each worker creating its own
+    /// `clang_createIndex`/`CXTranslationUnit` (see `parse_and_visit_file`) so no libclang
+    /// parsing state is shared across threads. A failed file logs and is skipped, the same as
+    /// the previous sequential behavior; it never aborts the batch.
     pub fn analyze_files(&mut self, source_files: &[SourceFile]) -> Result<(), String> {
         info!("Starting AST analysis for {} files...", source_files.len());
-        for source_file in source_files {
-            debug!("Analyzing file: {}", source_file.absolute_path.display());
-            match self.parse_and_visit_file(&source_file.absolute_path) {
-                Ok(file_ast_info) => {
-                    info!("Successfully analyzed AST for: {}", source_file.absolute_path.display());
-                    self.analysis_results.insert(source_file.absolute_path.clone(), file_ast_info);
+
+        let files_to_parse: Vec<&SourceFile> = source_files
+            .iter()
+            .filter(|source_file| {
+                let path_str = source_file.absolute_path.to_string_lossy();
+                let keep = self.passes_filters(&path_str);
+                if !keep {
+                    debug!("Skipping file excluded by filters: {}", source_file.absolute_path.display());
                 }
+                keep
+            })
+            .collect();
+
+        let parse_all = || self.parse_files_in_parallel(&files_to_parse);
+        let results = match self.config.analysis.max_threads {
+            Some(max_threads) => match rayon::ThreadPoolBuilder::new().num_threads(max_threads).build() {
+                Ok(pool) => pool.install(parse_all),
                 Err(e) => {
-                    error!("Failed to analyze AST for {}: {}", source_file.absolute_path.display(), e);
+                    warn!("Failed to build a thread pool with max_threads={}: {}. Falling back to rayon's default pool.", max_threads, e);
+                    parse_all()
                 }
+            },
+            None => parse_all(),
+        };
+
+        self.analysis_results = HashMap::with_capacity(files_to_parse.len());
+        for (file_path, maybe_ast_info) in results {
+            if let Some(file_ast_info) = maybe_ast_info {
+                self.analysis_results.insert(file_path, file_ast_info);
             }
         }
+
         info!("AST analysis finished. Results collected for {} files.", self.analysis_results.len());
         Ok(())
     }
 
+    fn parse_files_in_parallel(&self, files: &[&SourceFile]) -> Vec<(PathBuf, Option<FileAstInfo>)> {
+        files
+            .par_iter()
+            .map(|source_file| {
+                debug!("Analyzing file: {}", source_file.absolute_path.display());
+                match self.parse_and_visit_file(&source_file.absolute_path) {
+                    Ok(file_ast_info) => {
+                        info!("Successfully analyzed AST for: {}", source_file.absolute_path.display());
+                        (source_file.absolute_path.clone(), Some(file_ast_info))
+                    }
+                    Err(e) => {
+                        error!("Failed to analyze AST for {}: {}", source_file.absolute_path.display(), e);
+                        (source_file.absolute_path.clone(), None)
+                    }
+                }
+            })
+            .collect()
+    }
+
     fn parse_and_visit_file(&self, file_path: &Path) -> Result<FileAstInfo, String> {
         info!("Parsing and visiting file: {}", file_path.display());
         let mut ast_info = FileAstInfo::new(file_path.to_path_buf());
@@ -160,14 +325,11 @@ impl<'a> AstAnalyzer<'a> {
 
             let c_file_path = CString::new(file_path.to_string_lossy().as_bytes())
                 .map_err(|e| format!("Failed to convert file path to CString: {}", e))?;
-            
-            let arg1_std_cpp17 = CString::new("-std=c++17").unwrap();
-            let arg2_xcpp = CString::new("-xc++").unwrap();
-            let args_vec: Vec<*const c_char> = vec![
-                arg1_std_cpp17.as_ptr(),
-                arg2_xcpp.as_ptr(),
-            ];
-            
+
+            let default_args = vec![CString::new("-std=c++17").unwrap(), CString::new("-xc++").unwrap()];
+            let compile_args = self.compile_args_for_file(file_path).unwrap_or(default_args);
+            let args_vec: Vec<*const c_char> = compile_args.iter().map(|a| a.as_ptr()).collect();
+
             let tu = clang_parseTranslationUnit(
                 index, c_file_path.as_ptr(), args_vec.as_ptr(), args_vec.len() as i32,
                 std::ptr::null_mut(), 0, CXTranslationUnit_None,
@@ -208,6 +370,10 @@ impl<'a> AstAnalyzer<'a> {
                 current_file_path: file_path,
                 current_function_qname: None,
                 current_class_name: None,
+                tu,
+                include_filters: &self.include_filters,
+                exclude_filters: &self.exclude_filters,
+                mode: self.config.analysis.mode,
             };
 
             clang_visitChildren(
@@ -255,7 +421,72 @@ fn get_cursor_source_text(cursor: CXCursor, tu: CXTranslationUnit) -> Option<Str
     Some(text)
 }
 
-#[allow(non_upper_case_globals)] 
+#[allow(dead_code)]
+extern "C" fn collect_children_visitor(
+    cursor: CXCursor,
+    _parent: CXCursor,
+    client_data: *mut std::ffi::c_void,
+) -> CXChildVisitResult {
+    let children = unsafe { &mut *(client_data as *mut Vec<CXCursor>) };
+    children.push(cursor);
+    CXChildVisit_Continue
+}
+
+/// Returns `cursor`'s immediate children, via a one-level (non-recursing) `clang_visitChildren`
+/// pass.
+#[allow(dead_code)]
+fn get_immediate_children(cursor: CXCursor) -> Vec<CXCursor> {
+    let mut children: Vec<CXCursor> = Vec::new();
+    unsafe {
+        clang_visitChildren(
+            cursor,
+            collect_children_visitor,
+            &mut children as *mut _ as *mut std::ffi::c_void,
+        );
+    }
+    children
+}
+
+#[allow(non_upper_case_globals)]
+fn is_expression_cursor(kind: CXCursorKind) -> bool {
+    (CXCursor_FirstExpr..=CXCursor_LastExpr).contains(&kind)
+}
+
+/// Extracts the source text of a control-flow cursor's condition sub-expression, or `None` if it
+/// has none (e.g. `for(;;)`). `kind` must be one of `IfStmt`/`WhileStmt`/`SwitchStmt`/`DoStmt`/
+/// `ForStmt`.
+#[allow(non_upper_case_globals)]
+fn extract_condition_text(cursor: CXCursor, kind: CXCursorKind, tu: CXTranslationUnit) -> Option<String> {
+    let children = get_immediate_children(cursor);
+
+    let condition_cursor = match kind {
+        CXCursor_IfStmt | CXCursor_WhileStmt | CXCursor_SwitchStmt => {
+            // The condition is the first expression child; an optional condition-variable
+            // VarDecl (e.g. `if (int x = foo())`) isn't an expression kind, so it's skipped
+            // naturally, as is the trailing body CompoundStmt.
+            children.into_iter().find(|c| is_expression_cursor(unsafe { clang_getCursorKind(*c) }))
+        }
+        CXCursor_DoStmt => {
+            // `do { ... } while (cond)`: the children are [body, cond], so the condition is the
+            // last one, whether or not the body is a CompoundStmt.
+            children.into_iter().last()
+        }
+        CXCursor_ForStmt => {
+            // ForStmt children are some subset of [init, cond, inc, body]; excluding the
+            // trailing body, the condition is the middle one of what's left.
+            let non_body: Vec<CXCursor> = children
+                .into_iter()
+                .filter(|c| unsafe { clang_getCursorKind(*c) } != CXCursor_CompoundStmt)
+                .collect();
+            non_body.get(1).copied().filter(|c| is_expression_cursor(unsafe { clang_getCursorKind(*c) }))
+        }
+        _ => None,
+    }?;
+
+    get_cursor_source_text(condition_cursor, tu)
+}
+
+#[allow(non_upper_case_globals)]
 extern "C" fn visit_cursor_recursive(
     cursor: CXCursor,
     _parent: CXCursor, 
@@ -291,6 +522,10 @@ extern "C" fn visit_cursor_recursive(
                 let usr = cxstring_to_string(unsafe { clang_getCursorUSR(cursor) });
                 let qualified_name = if usr.is_empty() { name.clone() } else { usr };
 
+                if !passes_filters(&qualified_name, data.include_filters, data.exclude_filters) {
+                    return CXChildVisit_Continue;
+                }
+
                 let cursor_type = unsafe { clang_getCursorType(cursor) };
                 let result_type = unsafe { clang_getResultType(cursor_type) };
                 let return_type_str = cxstring_to_string(unsafe { clang_getTypeSpelling(result_type) });
@@ -309,93 +544,109 @@ extern "C" fn visit_cursor_recursive(
                     }
                 }
                 
-                let func_info = FunctionInfo {
-                    name,
-                    qualified_name: qualified_name.clone(),
-                    start_location: start_loc,
-                    end_location: end_loc,
-                    is_method: kind == CXCursor_CXXMethod,
-                    class_name: if kind == CXCursor_CXXMethod { data.current_class_name.clone() } else { None },
-                    return_type: return_type_str,
-                    parameters,
-                    has_body: true,
-                };
-                data.ast_info.functions.push(func_info);
+                if data.mode.includes_functions() {
+                    let func_info = FunctionInfo {
+                        name,
+                        qualified_name: qualified_name.clone(),
+                        start_location: start_loc,
+                        end_location: end_loc,
+                        is_method: kind == CXCursor_CXXMethod,
+                        class_name: if kind == CXCursor_CXXMethod { data.current_class_name.clone() } else { None },
+                        return_type: return_type_str,
+                        parameters,
+                        has_body: true,
+                    };
+                    data.ast_info.functions.push(func_info);
+                }
+                // Tracked unconditionally so branches/exceptions nested in this function still
+                // get correct parent attribution even when `Functions` isn't in the active mode.
                 data.current_function_qname = Some(qualified_name);
             }
         }
         CXCursor_IfStmt => {
-            if let Some(func_qname) = &data.current_function_qname {
-                data.ast_info.branches.push(BranchInfo {
-                    parent_function_qualified_name: func_qname.clone(),
-                    branch_type: "if".to_string(),
-                    start_location: start_loc,
-                    end_location: end_loc, 
-                    condition_expression: None, 
-                });
+            if data.mode.includes_branches() {
+                if let Some(func_qname) = &data.current_function_qname {
+                    data.ast_info.branches.push(BranchInfo {
+                        parent_function_qualified_name: func_qname.clone(),
+                        branch_type: "if".to_string(),
+                        start_location: start_loc,
+                        end_location: end_loc,
+                        condition_expression: extract_condition_text(cursor, kind, data.tu),
+                    });
+                }
             }
         }
         CXCursor_SwitchStmt => {
-             if let Some(func_qname) = &data.current_function_qname {
-                data.ast_info.branches.push(BranchInfo {
-                    parent_function_qualified_name: func_qname.clone(),
-                    branch_type: "switch".to_string(),
-                    start_location: start_loc,
-                    end_location: end_loc,
-                    condition_expression: None, 
-                });
+            if data.mode.includes_branches() {
+                if let Some(func_qname) = &data.current_function_qname {
+                    data.ast_info.branches.push(BranchInfo {
+                        parent_function_qualified_name: func_qname.clone(),
+                        branch_type: "switch".to_string(),
+                        start_location: start_loc,
+                        end_location: end_loc,
+                        condition_expression: extract_condition_text(cursor, kind, data.tu),
+                    });
+                }
             }
         }
         CXCursor_CaseStmt | CXCursor_DefaultStmt => {
-            if let Some(func_qname) = &data.current_function_qname {
-                data.ast_info.branches.push(BranchInfo {
-                    parent_function_qualified_name: func_qname.clone(),
-                    branch_type: if kind == CXCursor_CaseStmt { "case" } else { "default" }.to_string(),
-                    start_location: start_loc,
-                    end_location: end_loc,
-                    condition_expression: None, 
-                });
+            if data.mode.includes_branches() {
+                if let Some(func_qname) = &data.current_function_qname {
+                    data.ast_info.branches.push(BranchInfo {
+                        parent_function_qualified_name: func_qname.clone(),
+                        branch_type: if kind == CXCursor_CaseStmt { "case" } else { "default" }.to_string(),
+                        start_location: start_loc,
+                        end_location: end_loc,
+                        condition_expression: None,
+                    });
+                }
             }
         }
         CXCursor_ForStmt | CXCursor_WhileStmt | CXCursor_DoStmt => {
-            if let Some(func_qname) = &data.current_function_qname {
-                let branch_type = match kind {
-                    CXCursor_ForStmt => "for",
-                    CXCursor_WhileStmt => "while",
-                    CXCursor_DoStmt => "do_while",
-                    _ => unreachable!(),
-                }.to_string();
-                data.ast_info.branches.push(BranchInfo {
-                    parent_function_qualified_name: func_qname.clone(),
-                    branch_type,
-                    start_location: start_loc,
-                    end_location: end_loc,
-                    condition_expression: None, 
-                });
+            if data.mode.includes_branches() {
+                if let Some(func_qname) = &data.current_function_qname {
+                    let branch_type = match kind {
+                        CXCursor_ForStmt => "for",
+                        CXCursor_WhileStmt => "while",
+                        CXCursor_DoStmt => "do_while",
+                        _ => unreachable!(),
+                    }.to_string();
+                    data.ast_info.branches.push(BranchInfo {
+                        parent_function_qualified_name: func_qname.clone(),
+                        branch_type,
+                        start_location: start_loc,
+                        end_location: end_loc,
+                        condition_expression: extract_condition_text(cursor, kind, data.tu),
+                    });
+                }
             }
         }
         CXCursor_CXXTryStmt => {
-             if let Some(func_qname) = &data.current_function_qname {
-                data.ast_info.exceptions.push(ExceptionInfo {
-                    parent_function_qualified_name: func_qname.clone(),
-                    exception_type: "try".to_string(),
-                    caught_type: None,
-                    start_location: start_loc,
-                    end_location: end_loc,
-                });
+            if data.mode.includes_exceptions() {
+                if let Some(func_qname) = &data.current_function_qname {
+                    data.ast_info.exceptions.push(ExceptionInfo {
+                        parent_function_qualified_name: func_qname.clone(),
+                        exception_type: "try".to_string(),
+                        caught_type: None,
+                        start_location: start_loc,
+                        end_location: end_loc,
+                    });
+                }
             }
         }
         CXCursor_CXXCatchStmt => {
-            if let Some(func_qname) = &data.current_function_qname {
-                let caught_type_cursor = unsafe { clang_getCursorType(cursor) }; 
-                let caught_type_str = cxstring_to_string(unsafe { clang_getTypeSpelling(caught_type_cursor) });
-                data.ast_info.exceptions.push(ExceptionInfo {
-                    parent_function_qualified_name: func_qname.clone(),
-                    exception_type: "catch".to_string(),
-                    caught_type: if caught_type_str.is_empty() || caught_type_str == "..." { None } else { Some(caught_type_str) },
-                    start_location: start_loc,
-                    end_location: end_loc,
-                });
+            if data.mode.includes_exceptions() {
+                if let Some(func_qname) = &data.current_function_qname {
+                    let caught_type_cursor = unsafe { clang_getCursorType(cursor) };
+                    let caught_type_str = cxstring_to_string(unsafe { clang_getTypeSpelling(caught_type_cursor) });
+                    data.ast_info.exceptions.push(ExceptionInfo {
+                        parent_function_qualified_name: func_qname.clone(),
+                        exception_type: "catch".to_string(),
+                        caught_type: if caught_type_str.is_empty() || caught_type_str == "..." { None } else { Some(caught_type_str) },
+                        start_location: start_loc,
+                        end_location: end_loc,
+                    });
+                }
             }
         }
         _ => {}
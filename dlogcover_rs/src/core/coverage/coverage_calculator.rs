@@ -1,14 +1,15 @@
 use std::path::PathBuf;
 use std::collections::HashMap;
 use crate::core::ast_analyzer::{SourceLocation, FunctionInfo, BranchInfo, ExceptionInfo, FileAstInfo};
+use crate::core::coverage::coverage_rules::CoverageRuleEngine;
 use crate::core::log_identifier::LogCallSite;
 use crate::config::Config;
 use log::{debug, info, warn};
-use serde::Serialize; // Added Serialize
+use serde::{Deserialize, Serialize};
 
 // --- Coverage Statistics Structs ---
 
-#[derive(Debug, Clone, Default, Serialize)] // Added Serialize
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct CoverageMetrics {
     pub total: usize,
@@ -37,7 +38,7 @@ impl CoverageMetrics {
     }
 }
 
-#[derive(Debug, Clone, Serialize)] // Added Serialize
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct PerFileCoverage {
     pub file_path: PathBuf,
@@ -45,9 +46,16 @@ pub struct PerFileCoverage {
     pub branches: CoverageMetrics,
     pub exceptions: CoverageMetrics,
     pub overall: CoverageMetrics,
-    pub uncovered_functions: Vec<FunctionInfo>, 
+    pub uncovered_functions: Vec<FunctionInfo>,
     pub uncovered_branches: Vec<BranchInfo>,
     pub uncovered_exceptions: Vec<ExceptionInfo>,
+    /// The identities backing `functions.covered`, kept alongside `uncovered_functions` so
+    /// reporters that must list *every* function (e.g. `LcovReporter`'s `FN`/`FNDA` records,
+    /// `CoberturaReporter`'s `<method>` elements) aren't limited to the uncovered ones.
+    pub covered_functions: Vec<FunctionInfo>,
+    /// The identities backing `branches.covered`, for the same reason as `covered_functions`
+    /// (e.g. `LcovReporter`'s `BRDA` records).
+    pub covered_branches: Vec<BranchInfo>,
 }
 
 impl PerFileCoverage {
@@ -62,11 +70,13 @@ impl PerFileCoverage {
             uncovered_functions: Vec::new(),
             uncovered_branches: Vec::new(),
             uncovered_exceptions: Vec::new(),
+            covered_functions: Vec::new(),
+            covered_branches: Vec::new(),
         }
     }
 }
 
-#[derive(Debug, Clone, Default, Serialize)] // Added Serialize
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct ProjectCoverage {
     pub files: Vec<PerFileCoverage>,
@@ -89,21 +99,20 @@ impl<'a> CoverageCalculator<'a> {
         CoverageCalculator { config }
     }
 
-    fn is_location_within_range(
-        log_loc: &SourceLocation,
-        item_start_loc: &SourceLocation,
-        item_end_loc: &SourceLocation,
+    /// Whether any log site in `sorted_positions` (sorted ascending by `(line, column)`) falls
+    /// within `[start, end]`. Since columns only matter on the boundary lines, containment is
+    /// exactly "the first site at or after `start` is at or before `end`" under plain
+    /// `(line, column)` lexicographic ordering, so this is found with one binary search instead
+    /// of scanning every site.
+    fn has_site_in_range(
+        sorted_positions: &[(u32, u32)],
+        start: &SourceLocation,
+        end: &SourceLocation,
     ) -> bool {
-        if log_loc.line < item_start_loc.line || log_loc.line > item_end_loc.line {
-            return false;
-        }
-        if log_loc.line == item_start_loc.line && log_loc.column < item_start_loc.column {
-            return false;
-        }
-        if log_loc.line == item_end_loc.line && log_loc.column > item_end_loc.column {
-            return false;
-        }
-        true
+        let start_key = (start.line, start.column);
+        let end_key = (end.line, end.column);
+        let idx = sorted_positions.partition_point(|pos| *pos < start_key);
+        sorted_positions.get(idx).is_some_and(|pos| *pos <= end_key)
     }
 
     fn calculate_file_coverage(
@@ -114,15 +123,25 @@ impl<'a> CoverageCalculator<'a> {
         info!("Calculating coverage for file: {}", file_ast.file_path.display());
         let mut file_coverage = PerFileCoverage::new(file_ast.file_path.clone());
 
+        let mut sorted_positions: Vec<(u32, u32)> = log_sites
+            .iter()
+            .map(|site| (site.source_location.line, site.source_location.column))
+            .collect();
+        sorted_positions.sort_unstable();
+
+        let logged_function_names: std::collections::HashSet<&str> = log_sites
+            .iter()
+            .map(|site| site.parent_function_qualified_name.as_str())
+            .collect();
+
         file_coverage.functions.total = file_ast.functions.iter().filter(|f| f.has_body).count();
         for func_info in file_ast.functions.iter().filter(|f| f.has_body) {
-            let is_covered = log_sites.iter().any(|log_site| {
-                log_site.parent_function_qualified_name == func_info.qualified_name ||
-                Self::is_location_within_range(&log_site.source_location, &func_info.start_location, &func_info.end_location)
-            });
+            let is_covered = logged_function_names.contains(func_info.qualified_name.as_str())
+                || Self::has_site_in_range(&sorted_positions, &func_info.start_location, &func_info.end_location);
 
             if is_covered {
                 file_coverage.functions.covered += 1;
+                file_coverage.covered_functions.push(func_info.clone());
             } else {
                 file_coverage.uncovered_functions.push(func_info.clone());
             }
@@ -132,11 +151,10 @@ impl<'a> CoverageCalculator<'a> {
 
         file_coverage.branches.total = file_ast.branches.len();
         for branch_info in &file_ast.branches {
-            let is_covered = log_sites.iter().any(|log_site| {
-                Self::is_location_within_range(&log_site.source_location, &branch_info.start_location, &branch_info.end_location)
-            });
+            let is_covered = Self::has_site_in_range(&sorted_positions, &branch_info.start_location, &branch_info.end_location);
             if is_covered {
                 file_coverage.branches.covered += 1;
+                file_coverage.covered_branches.push(branch_info.clone());
             } else {
                 file_coverage.uncovered_branches.push(branch_info.clone());
             }
@@ -146,9 +164,7 @@ impl<'a> CoverageCalculator<'a> {
 
         file_coverage.exceptions.total = file_ast.exceptions.len();
         for exc_info in &file_ast.exceptions {
-            let is_covered = log_sites.iter().any(|log_site| {
-                Self::is_location_within_range(&log_site.source_location, &exc_info.start_location, &exc_info.end_location)
-            });
+            let is_covered = Self::has_site_in_range(&sorted_positions, &exc_info.start_location, &exc_info.end_location);
             if is_covered {
                 file_coverage.exceptions.covered += 1;
             } else {
@@ -179,12 +195,15 @@ impl<'a> CoverageCalculator<'a> {
             return Ok(project_coverage);
         }
 
+        let rule_engine = CoverageRuleEngine::new(self.config);
+
         for (file_path, file_ast_info) in ast_results {
-            let empty_log_sites = Vec::new(); 
+            let empty_log_sites = Vec::new();
             let log_sites_for_file = log_sites_map.get(file_path).unwrap_or(&empty_log_sites);
-            
+
             debug!("Processing file for project coverage: {}", file_path.display());
-            let file_cov = self.calculate_file_coverage(file_ast_info, log_sites_for_file);
+            let adjusted_ast_info = rule_engine.apply(file_ast_info);
+            let file_cov = self.calculate_file_coverage(&adjusted_ast_info, log_sites_for_file);
 
             project_coverage.total_functions.total += file_cov.functions.total;
             project_coverage.total_functions.covered += file_cov.functions.covered;
@@ -0,0 +1,163 @@
+use crate::config::Config;
+use crate::core::ast_analyzer::FileAstInfo;
+use log::{debug, info};
+
+/// A rule that inspects a file's AST and decides which functions/branches should be
+/// reclassified as "not requiring a log" before coverage is computed.
+#[allow(dead_code)]
+pub trait CoverageRule {
+    fn name(&self) -> &str;
+
+    /// Returns the qualified names of functions to exclude from coverage counting.
+    fn excluded_function_names(&self, file_ast: &FileAstInfo) -> Vec<String> {
+        let _ = file_ast;
+        Vec::new()
+    }
+
+    /// Returns the indices (into `file_ast.branches`) of branches to exclude.
+    fn excluded_branch_indices(&self, file_ast: &FileAstInfo) -> Vec<usize> {
+        let _ = file_ast;
+        Vec::new()
+    }
+}
+
+/// Skips functions whose body spans fewer than `min_lines` source lines.
+#[allow(dead_code)]
+pub struct MinFunctionLinesRule {
+    pub min_lines: usize,
+}
+
+impl CoverageRule for MinFunctionLinesRule {
+    fn name(&self) -> &str {
+        "min_function_lines"
+    }
+
+    fn excluded_function_names(&self, file_ast: &FileAstInfo) -> Vec<String> {
+        file_ast
+            .functions
+            .iter()
+            .filter(|f| {
+                let span = f.end_location.line.saturating_sub(f.start_location.line) as usize + 1;
+                span < self.min_lines
+            })
+            .map(|f| f.qualified_name.clone())
+            .collect()
+    }
+}
+
+/// Skips trivial accessors: single-parameter-or-fewer getters/setters identified by name.
+#[allow(dead_code)]
+pub struct TrivialAccessorRule;
+
+impl CoverageRule for TrivialAccessorRule {
+    fn name(&self) -> &str {
+        "trivial_accessor"
+    }
+
+    fn excluded_function_names(&self, file_ast: &FileAstInfo) -> Vec<String> {
+        file_ast
+            .functions
+            .iter()
+            .filter(|f| {
+                let name_lower = f.name.to_lowercase();
+                let looks_like_accessor = name_lower.starts_with("get") || name_lower.starts_with("set")
+                    || name_lower.starts_with("is") || name_lower.starts_with("has");
+                looks_like_accessor && f.parameters.len() <= 1
+            })
+            .map(|f| f.qualified_name.clone())
+            .collect()
+    }
+}
+
+/// Skips branches whose body is empty (start and end location land on the same line).
+#[allow(dead_code)]
+pub struct EmptyBranchRule;
+
+impl CoverageRule for EmptyBranchRule {
+    fn name(&self) -> &str {
+        "empty_branch"
+    }
+
+    fn excluded_branch_indices(&self, file_ast: &FileAstInfo) -> Vec<usize> {
+        file_ast
+            .branches
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.start_location.line == b.end_location.line)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+}
+
+/// Runs a pluggable list of `CoverageRule`s over a `FileAstInfo`, removing items that rules
+/// decide don't require logging, and logging how many items each rule removed.
+#[allow(dead_code)]
+pub struct CoverageRuleEngine {
+    rules: Vec<Box<dyn CoverageRule>>,
+}
+
+#[allow(dead_code)]
+impl CoverageRuleEngine {
+    pub fn new(config: &Config) -> Self {
+        let mut rules: Vec<Box<dyn CoverageRule>> = Vec::new();
+        if config.coverage_rules.enabled {
+            rules.push(Box::new(MinFunctionLinesRule {
+                min_lines: config.coverage_rules.min_function_lines,
+            }));
+            rules.push(Box::new(TrivialAccessorRule));
+            rules.push(Box::new(EmptyBranchRule));
+        }
+        CoverageRuleEngine { rules }
+    }
+
+    /// Applies every rule to `file_ast`, returning a copy with excluded functions/branches
+    /// removed.
+    pub fn apply(&self, file_ast: &FileAstInfo) -> FileAstInfo {
+        let mut adjusted = file_ast.clone();
+
+        for rule in &self.rules {
+            let excluded_functions = rule.excluded_function_names(file_ast);
+            let excluded_branches = rule.excluded_branch_indices(file_ast);
+
+            if !excluded_functions.is_empty() {
+                let before = adjusted.functions.len();
+                adjusted
+                    .functions
+                    .retain(|f| !excluded_functions.contains(&f.qualified_name));
+                let removed = before - adjusted.functions.len();
+                if removed > 0 {
+                    info!(
+                        "CoverageRule '{}' excluded {} function(s) in {}",
+                        rule.name(),
+                        removed,
+                        file_ast.file_path.display()
+                    );
+                }
+            }
+
+            if !excluded_branches.is_empty() {
+                let before = adjusted.branches.len();
+                let excluded_locations: Vec<_> = excluded_branches
+                    .iter()
+                    .map(|&idx| &file_ast.branches[idx].start_location)
+                    .collect();
+                adjusted
+                    .branches
+                    .retain(|b| !excluded_locations.iter().any(|loc| loc.line == b.start_location.line && loc.column == b.start_location.column));
+                let removed = before - adjusted.branches.len();
+                if removed > 0 {
+                    info!(
+                        "CoverageRule '{}' excluded {} branch(es) in {}",
+                        rule.name(),
+                        removed,
+                        file_ast.file_path.display()
+                    );
+                }
+            }
+
+            debug!("CoverageRule '{}' evaluated for {}", rule.name(), file_ast.file_path.display());
+        }
+
+        adjusted
+    }
+}
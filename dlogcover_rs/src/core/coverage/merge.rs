@@ -0,0 +1,169 @@
+use super::coverage_calculator::{CoverageMetrics, PerFileCoverage, ProjectCoverage};
+use crate::core::ast_analyzer::{BranchInfo, ExceptionInfo, FunctionInfo};
+use log::{debug, info};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Unions a set of `PerFileCoverage` entries for the same file collected from separate runs:
+/// an item counts as covered if it's covered in *any* run, and stays in `uncovered_*` only if
+/// it's uncovered in *every* run that reports it. Errors if the AST-derived item counts
+/// (`functions.total`, `branches.total`, `exceptions.total`) disagree between runs, since that
+/// means the runs analyzed different versions of the file.
+fn merge_file_coverage(file_path: &PathBuf, runs: &[&PerFileCoverage]) -> Result<PerFileCoverage, String> {
+    let first = runs[0];
+    for run in &runs[1..] {
+        if run.functions.total != first.functions.total
+            || run.branches.total != first.branches.total
+            || run.exceptions.total != first.exceptions.total
+        {
+            return Err(format!(
+                "merge: item counts for '{}' disagree between runs (functions {}/{}, branches {}/{}, exceptions {}/{})",
+                file_path.display(),
+                first.functions.total, run.functions.total,
+                first.branches.total, run.branches.total,
+                first.exceptions.total, run.exceptions.total,
+            ));
+        }
+    }
+
+    let uncovered_functions: Vec<FunctionInfo> = intersect_by_key(
+        runs.iter().map(|r| &r.uncovered_functions),
+        |f: &FunctionInfo| f.qualified_name.clone(),
+    );
+    let uncovered_names: std::collections::HashSet<&str> =
+        uncovered_functions.iter().map(|f| f.qualified_name.as_str()).collect();
+    let mut known_functions: HashMap<String, FunctionInfo> = HashMap::new();
+    for run in runs {
+        for f in run.covered_functions.iter().chain(run.uncovered_functions.iter()) {
+            known_functions.entry(f.qualified_name.clone()).or_insert_with(|| f.clone());
+        }
+    }
+    let mut covered_functions: Vec<FunctionInfo> = known_functions
+        .into_values()
+        .filter(|f| !uncovered_names.contains(f.qualified_name.as_str()))
+        .collect();
+    covered_functions.sort_by(|a, b| a.qualified_name.cmp(&b.qualified_name));
+    let uncovered_branches: Vec<BranchInfo> = intersect_by_key(
+        runs.iter().map(|r| &r.uncovered_branches),
+        |b: &BranchInfo| (b.start_location.line, b.start_location.column),
+    );
+    let uncovered_branch_keys: std::collections::HashSet<(u32, u32)> = uncovered_branches
+        .iter()
+        .map(|b| (b.start_location.line, b.start_location.column))
+        .collect();
+    let mut known_branches: HashMap<(u32, u32), BranchInfo> = HashMap::new();
+    for run in runs {
+        for b in run.covered_branches.iter().chain(run.uncovered_branches.iter()) {
+            known_branches
+                .entry((b.start_location.line, b.start_location.column))
+                .or_insert_with(|| b.clone());
+        }
+    }
+    let mut covered_branches: Vec<BranchInfo> = known_branches
+        .into_iter()
+        .filter(|(key, _)| !uncovered_branch_keys.contains(key))
+        .map(|(_, b)| b)
+        .collect();
+    covered_branches.sort_by(|a, b| {
+        (a.start_location.line, a.start_location.column).cmp(&(b.start_location.line, b.start_location.column))
+    });
+    let uncovered_exceptions: Vec<ExceptionInfo> = intersect_by_key(
+        runs.iter().map(|r| &r.uncovered_exceptions),
+        |e: &ExceptionInfo| (e.start_location.line, e.start_location.column),
+    );
+
+    let mut merged = PerFileCoverage::new(file_path.clone());
+    merged.functions = CoverageMetrics::new(first.functions.total, first.functions.total - uncovered_functions.len());
+    merged.branches = CoverageMetrics::new(first.branches.total, first.branches.total - uncovered_branches.len());
+    merged.exceptions = CoverageMetrics::new(first.exceptions.total, first.exceptions.total - uncovered_exceptions.len());
+
+    let total_items = merged.functions.total + merged.branches.total + merged.exceptions.total;
+    let covered_items = merged.functions.covered + merged.branches.covered + merged.exceptions.covered;
+    merged.overall = CoverageMetrics::new(total_items, covered_items);
+
+    merged.uncovered_functions = uncovered_functions;
+    merged.uncovered_branches = uncovered_branches;
+    merged.uncovered_exceptions = uncovered_exceptions;
+    merged.covered_functions = covered_functions;
+    merged.covered_branches = covered_branches;
+
+    Ok(merged)
+}
+
+/// Keeps only items present in *every* run's list, deduplicated by `key`.
+fn intersect_by_key<'a, T, K, I>(lists: I, key: impl Fn(&T) -> K) -> Vec<T>
+where
+    T: Clone + 'a,
+    K: std::hash::Hash + Eq,
+    I: Iterator<Item = &'a Vec<T>> + Clone,
+{
+    let mut lists_iter = lists.clone();
+    let first_list = match lists_iter.next() {
+        Some(list) => list,
+        None => return Vec::new(),
+    };
+    let remaining: Vec<&Vec<T>> = lists_iter.collect();
+
+    let mut seen = std::collections::HashSet::new();
+    let mut result = Vec::new();
+    for item in first_list {
+        let k = key(item);
+        if seen.contains(&k) {
+            continue;
+        }
+        if remaining.iter().all(|list| list.iter().any(|other| key(other) == k)) {
+            seen.insert(k);
+            result.push(item.clone());
+        }
+    }
+    result
+}
+
+/// Merges multiple `ProjectCoverage` runs (e.g. from sharded or incremental analyses) into a
+/// single combined result, unioning per-file coverage by `file_path` and recomputing all
+/// percentages and totals afterward.
+#[allow(dead_code)]
+pub fn merge(runs: &[ProjectCoverage]) -> Result<ProjectCoverage, String> {
+    info!("Merging {} coverage run(s)...", runs.len());
+
+    let mut by_file: HashMap<PathBuf, Vec<&PerFileCoverage>> = HashMap::new();
+    for run in runs {
+        for file_coverage in &run.files {
+            by_file.entry(file_coverage.file_path.clone()).or_default().push(file_coverage);
+        }
+    }
+
+    let mut project_coverage = ProjectCoverage::default();
+    let mut file_paths: Vec<&PathBuf> = by_file.keys().collect();
+    file_paths.sort();
+
+    for file_path in file_paths {
+        let runs_for_file = &by_file[file_path];
+        debug!("Merging {} run(s) of coverage for {}", runs_for_file.len(), file_path.display());
+        let merged_file = merge_file_coverage(file_path, runs_for_file)?;
+
+        project_coverage.total_functions.total += merged_file.functions.total;
+        project_coverage.total_functions.covered += merged_file.functions.covered;
+        project_coverage.total_branches.total += merged_file.branches.total;
+        project_coverage.total_branches.covered += merged_file.branches.covered;
+        project_coverage.total_exceptions.total += merged_file.exceptions.total;
+        project_coverage.total_exceptions.covered += merged_file.exceptions.covered;
+
+        project_coverage.files.push(merged_file);
+    }
+
+    project_coverage.total_functions.calculate_percentage();
+    project_coverage.total_branches.calculate_percentage();
+    project_coverage.total_exceptions.calculate_percentage();
+
+    let overall_total = project_coverage.total_functions.total
+        + project_coverage.total_branches.total
+        + project_coverage.total_exceptions.total;
+    let overall_covered = project_coverage.total_functions.covered
+        + project_coverage.total_branches.covered
+        + project_coverage.total_exceptions.covered;
+    project_coverage.project_overall = CoverageMetrics::new(overall_total, overall_covered);
+
+    info!("Merge finished: {} file(s) combined.", project_coverage.files.len());
+    Ok(project_coverage)
+}
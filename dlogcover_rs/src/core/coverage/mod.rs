@@ -1,9 +1,17 @@
 pub mod coverage_calculator;
+pub mod coverage_rules;
+pub mod merge;
+pub mod threshold;
+pub mod suppression;
 
 // Re-export key structs and the calculator itself for easier access
 pub use coverage_calculator::{
-    CoverageCalculator, 
-    ProjectCoverage, 
-    PerFileCoverage, 
+    CoverageCalculator,
+    ProjectCoverage,
+    PerFileCoverage,
     CoverageMetrics
 };
+pub use coverage_rules::{CoverageRule, CoverageRuleEngine};
+pub use merge::merge;
+pub use threshold::check_thresholds;
+pub use suppression::suppress;
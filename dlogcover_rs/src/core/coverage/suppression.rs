@@ -0,0 +1,91 @@
+use super::{CoverageMetrics, ProjectCoverage};
+use crate::config::SuppressionConfig;
+use log::warn;
+use regex::Regex;
+
+/// Runs `config`'s ignore lists over an already-computed [`ProjectCoverage`], dropping matched
+/// entries from each file's `uncovered_functions`/`uncovered_branches`/`uncovered_exceptions` and
+/// its `CoverageMetrics.total` (never `covered` — a suppressed item is removed from the
+/// denominator, not counted as logged), then recomputing every percentage and project-wide
+/// rollup. Unlike `CoverageRuleEngine` (which excludes items from the AST *before* coverage is
+/// calculated), this runs after calculation, directly against the computed report.
+pub fn suppress(project_coverage: &mut ProjectCoverage, config: &SuppressionConfig) {
+    if config.ignore_patterns.is_empty()
+        && config.ignore_attributes.is_empty()
+        && config.ignore_qualified_names.is_empty()
+    {
+        return;
+    }
+
+    let patterns: Vec<Regex> = config
+        .ignore_patterns
+        .iter()
+        .filter_map(|pattern| match Regex::new(pattern) {
+            Ok(re) => Some(re),
+            Err(e) => {
+                warn!("Suppression: ignoring invalid regex '{}': {}", pattern, e);
+                None
+            }
+        })
+        .collect();
+
+    let is_suppressed = |qualified_name: &str| -> bool {
+        config
+            .ignore_qualified_names
+            .iter()
+            .any(|name| name == qualified_name)
+            || config
+                .ignore_attributes
+                .iter()
+                .any(|attr| qualified_name.split("::").any(|segment| segment == attr))
+            || patterns.iter().any(|re| re.is_match(qualified_name))
+    };
+
+    for file_coverage in &mut project_coverage.files {
+        let before = file_coverage.uncovered_functions.len();
+        file_coverage
+            .uncovered_functions
+            .retain(|f| !is_suppressed(&f.qualified_name));
+        file_coverage.functions.total -= before - file_coverage.uncovered_functions.len();
+        file_coverage.functions.calculate_percentage();
+
+        let before = file_coverage.uncovered_branches.len();
+        file_coverage
+            .uncovered_branches
+            .retain(|b| !is_suppressed(&b.parent_function_qualified_name));
+        file_coverage.branches.total -= before - file_coverage.uncovered_branches.len();
+        file_coverage.branches.calculate_percentage();
+
+        let before = file_coverage.uncovered_exceptions.len();
+        file_coverage
+            .uncovered_exceptions
+            .retain(|e| !is_suppressed(&e.parent_function_qualified_name));
+        file_coverage.exceptions.total -= before - file_coverage.uncovered_exceptions.len();
+        file_coverage.exceptions.calculate_percentage();
+
+        let total_items = file_coverage.functions.total + file_coverage.branches.total + file_coverage.exceptions.total;
+        let covered_items = file_coverage.functions.covered + file_coverage.branches.covered + file_coverage.exceptions.covered;
+        file_coverage.overall = CoverageMetrics::new(total_items, covered_items);
+    }
+
+    project_coverage.total_functions = CoverageMetrics::new(
+        project_coverage.files.iter().map(|f| f.functions.total).sum(),
+        project_coverage.files.iter().map(|f| f.functions.covered).sum(),
+    );
+    project_coverage.total_branches = CoverageMetrics::new(
+        project_coverage.files.iter().map(|f| f.branches.total).sum(),
+        project_coverage.files.iter().map(|f| f.branches.covered).sum(),
+    );
+    project_coverage.total_exceptions = CoverageMetrics::new(
+        project_coverage.files.iter().map(|f| f.exceptions.total).sum(),
+        project_coverage.files.iter().map(|f| f.exceptions.covered).sum(),
+    );
+
+    let overall_total_items = project_coverage.total_functions.total
+        + project_coverage.total_branches.total
+        + project_coverage.total_exceptions.total;
+    let overall_covered_items = project_coverage.total_functions.covered
+        + project_coverage.total_branches.covered
+        + project_coverage.total_exceptions.covered;
+    project_coverage.project_overall = CoverageMetrics::new(overall_total_items, overall_covered_items);
+}
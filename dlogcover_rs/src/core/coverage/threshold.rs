@@ -0,0 +1,73 @@
+use super::{PerFileCoverage, ProjectCoverage};
+use crate::config::ReportConfig;
+
+/// Compares a computed [`ProjectCoverage`]'s percentages against the minimums configured on
+/// [`ReportConfig`]. Returns `Ok(())` if every configured minimum is met (fields left `None`
+/// impose no requirement), or `Err` with one human-readable description per metric/file that
+/// fell short.
+pub fn check_thresholds(
+    project_coverage: &ProjectCoverage,
+    report_config: &ReportConfig,
+) -> Result<(), Vec<String>> {
+    let mut failures = Vec::new();
+
+    check_metric(
+        "overall",
+        project_coverage.project_overall.percentage,
+        report_config.min_overall_coverage,
+        &mut failures,
+    );
+    check_metric(
+        "function",
+        project_coverage.total_functions.percentage,
+        report_config.min_function_coverage,
+        &mut failures,
+    );
+    check_metric(
+        "branch",
+        project_coverage.total_branches.percentage,
+        report_config.min_branch_coverage,
+        &mut failures,
+    );
+    check_metric(
+        "exception",
+        project_coverage.total_exceptions.percentage,
+        report_config.min_exception_coverage,
+        &mut failures,
+    );
+
+    if let Some(min_per_file) = report_config.min_per_file_coverage {
+        for file_coverage in &project_coverage.files {
+            check_file(file_coverage, min_per_file, &mut failures);
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(failures)
+    }
+}
+
+fn check_metric(label: &str, actual: f64, minimum: Option<f64>, failures: &mut Vec<String>) {
+    if let Some(minimum) = minimum {
+        if actual < minimum {
+            failures.push(format!(
+                "{} coverage {:.2}% is below the configured minimum of {:.2}%",
+                label, actual, minimum
+            ));
+        }
+    }
+}
+
+fn check_file(file_coverage: &PerFileCoverage, minimum: f64, failures: &mut Vec<String>) {
+    let actual = file_coverage.overall.percentage;
+    if actual < minimum {
+        failures.push(format!(
+            "{}: coverage {:.2}% is below the configured per-file minimum of {:.2}%",
+            file_coverage.file_path.display(),
+            actual,
+            minimum
+        ));
+    }
+}
@@ -1,6 +1,7 @@
 use std::path::{Path, PathBuf};
 use std::ffi::{CString, CStr};
 use std::os::raw::c_char;
+use std::collections::{HashMap, VecDeque};
 
 use crate::core::ast_analyzer::SourceLocation as AstSourceLocation;
 use crate::config::{Config, QtLogConfig, CustomLogConfig}; // Assuming these are public enough
@@ -8,6 +9,7 @@ use crate::core::ast_analyzer::FileAstInfo;
 // use crate::utils::file_utils; // Not used directly in this snippet
 use clang_sys::*;
 use log::{debug, error, info, warn};
+use regex::Regex;
 use serde::Serialize; // Added Serialize
 
 // --- Log Information Structs and Enums ---
@@ -18,11 +20,27 @@ pub enum LogLevel {
     Debug,
     Info,
     Warning,
-    Critical, 
-    Fatal,    
+    Critical,
+    Fatal,
     Unknown,
 }
 
+impl LogLevel {
+    /// Orders levels by severity (`Debug` lowest, `Fatal` highest) for `LogCallSiteQuery`'s
+    /// `min_level` filter. `Unknown` is treated as below `Debug` since it carries no severity
+    /// information of its own.
+    fn severity_rank(&self) -> u8 {
+        match self {
+            LogLevel::Unknown => 0,
+            LogLevel::Debug => 1,
+            LogLevel::Info => 2,
+            LogLevel::Warning => 3,
+            LogLevel::Critical => 4,
+            LogLevel::Fatal => 5,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)] // Added Serialize
 #[allow(dead_code)]
 pub enum LogType {
@@ -36,12 +54,200 @@ pub enum LogType {
 #[allow(dead_code)]
 pub struct LogCallSite {
     pub function_name: String,
-    pub source_location: AstSourceLocation, 
+    pub source_location: AstSourceLocation,
     pub log_level: LogLevel,
     pub log_type: LogType,
     pub parent_function_qualified_name: String,
     pub containing_class_name: Option<String>,
     pub message_arguments: Vec<String>,
+    /// The first `CXToken_Literal` spelling found among `message_arguments`, typically the log
+    /// call's format/message string. `None` if no argument contains a literal token.
+    pub format_string: Option<String>,
+}
+
+/// A filter builder over a `Vec<LogCallSite>`, so reporting and coverage tools can slice the
+/// sites discovered by `LogIdentifier` without re-parsing. Each `with_*` method narrows the
+/// query and returns `self` for chaining; call `apply` to get the matching sites, or
+/// `write_ndjson` to stream them out as newline-delimited JSON.
+#[derive(Default)]
+#[allow(dead_code)]
+pub struct LogCallSiteQuery {
+    min_level: Option<LogLevel>,
+    log_type: Option<LogType>,
+    name_pattern: Option<Regex>,
+    file_path_prefix: Option<PathBuf>,
+    parent_function_qualified_name: Option<String>,
+    limit: Option<usize>,
+}
+
+#[allow(dead_code)]
+impl LogCallSiteQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps only sites whose `log_level` is at or above `level` in severity.
+    pub fn with_min_level(mut self, level: LogLevel) -> Self {
+        self.min_level = Some(level);
+        self
+    }
+
+    pub fn with_log_type(mut self, log_type: LogType) -> Self {
+        self.log_type = Some(log_type);
+        self
+    }
+
+    /// Keeps only sites where `name_pattern` matches `function_name` or `format_string`.
+    pub fn with_name_pattern(mut self, name_pattern: Regex) -> Self {
+        self.name_pattern = Some(name_pattern);
+        self
+    }
+
+    /// Keeps only sites whose `source_location.file_path` starts with `prefix` (module scoping).
+    pub fn with_file_path_prefix(mut self, prefix: impl Into<PathBuf>) -> Self {
+        self.file_path_prefix = Some(prefix.into());
+        self
+    }
+
+    pub fn with_parent_function(mut self, qualified_name: impl Into<String>) -> Self {
+        self.parent_function_qualified_name = Some(qualified_name.into());
+        self
+    }
+
+    /// Caps the number of matching sites returned, in input order.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    fn matches(&self, site: &LogCallSite) -> bool {
+        if let Some(ref min_level) = self.min_level {
+            if site.log_level.severity_rank() < min_level.severity_rank() {
+                return false;
+            }
+        }
+        if let Some(ref log_type) = self.log_type {
+            if &site.log_type != log_type {
+                return false;
+            }
+        }
+        if let Some(ref name_pattern) = self.name_pattern {
+            let format_matches = site
+                .format_string
+                .as_deref()
+                .map(|s| name_pattern.is_match(s))
+                .unwrap_or(false);
+            if !name_pattern.is_match(&site.function_name) && !format_matches {
+                return false;
+            }
+        }
+        if let Some(ref prefix) = self.file_path_prefix {
+            if !site.source_location.file_path.starts_with(prefix) {
+                return false;
+            }
+        }
+        if let Some(ref qname) = self.parent_function_qualified_name {
+            if &site.parent_function_qualified_name != qname {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Returns the sites in `sites` matching this query, in input order, up to `limit` if set.
+    pub fn apply<'s>(&self, sites: &'s [LogCallSite]) -> Vec<&'s LogCallSite> {
+        let matching = sites.iter().filter(|site| self.matches(site));
+        match self.limit {
+            Some(limit) => matching.take(limit).collect(),
+            None => matching.collect(),
+        }
+    }
+
+    /// Streams the matching sites to `writer` as newline-delimited JSON (one `LogCallSite` per
+    /// line).
+    pub fn write_ndjson<W: std::io::Write>(&self, sites: &[LogCallSite], writer: &mut W) -> Result<(), String> {
+        for site in self.apply(sites) {
+            serde_json::to_writer(&mut *writer, site)
+                .map_err(|e| format!("LogCallSiteQuery: failed to serialize a log call site: {}", e))?;
+            writer
+                .write_all(b"\n")
+                .map_err(|e| format!("LogCallSiteQuery: failed to write newline: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Mirrors `CXDiagnosticSeverity`, ordered from least to most severe so it can be compared
+/// against the configured `analysis.fatal_diagnostic_severity` threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[allow(dead_code)]
+pub enum DiagnosticSeverity {
+    Ignored,
+    Note,
+    Warning,
+    Error,
+    Fatal,
+}
+
+impl DiagnosticSeverity {
+    fn from_cx(severity: CXDiagnosticSeverity) -> Self {
+        match severity {
+            CXDiagnostic_Note => DiagnosticSeverity::Note,
+            CXDiagnostic_Warning => DiagnosticSeverity::Warning,
+            CXDiagnostic_Error => DiagnosticSeverity::Error,
+            CXDiagnostic_Fatal => DiagnosticSeverity::Fatal,
+            _ => DiagnosticSeverity::Ignored,
+        }
+    }
+
+    /// Parses the `analysis.fatal_diagnostic_severity` config value into the minimum severity
+    /// that should abort parsing, or `None` for `"never"` (never abort). Falls back to `Error`
+    /// (the historical behavior) for anything unrecognized rather than erroring here; the value
+    /// itself is validated up front by `ConfigManager::validate_config`.
+    fn threshold_from_config(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "never" => None,
+            "fatal" => Some(DiagnosticSeverity::Fatal),
+            _ => Some(DiagnosticSeverity::Error),
+        }
+    }
+}
+
+/// A single replacement suggested by clang to resolve a diagnostic, pulled from
+/// `clang_getDiagnosticFixIt`.
+#[derive(Debug, Clone, Serialize)]
+#[allow(dead_code)]
+pub struct FixIt {
+    pub range_start: AstSourceLocation,
+    pub range_end: AstSourceLocation,
+    pub replacement: String,
+}
+
+/// A clang diagnostic "cooked" into a serializable, self-contained record, with any fix-it
+/// hints clang attached to it.
+#[derive(Debug, Clone, Serialize)]
+#[allow(dead_code)]
+pub struct ParseDiagnostic {
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+    pub location: AstSourceLocation,
+    pub fixits: Vec<FixIt>,
+}
+
+/// Reads all fix-its attached to `diagnostic` via `clang_getDiagnosticNumFixIts` /
+/// `clang_getDiagnosticFixIt`.
+#[allow(dead_code)]
+fn read_fixits(diagnostic: CXDiagnostic) -> Vec<FixIt> {
+    let num_fixits = unsafe { clang_getDiagnosticNumFixIts(diagnostic) };
+    let mut fixits = Vec::with_capacity(num_fixits as usize);
+    for i in 0..num_fixits {
+        let mut range = unsafe { std::mem::zeroed::<CXSourceRange>() };
+        let replacement = cxstring_to_string_log(unsafe { clang_getDiagnosticFixIt(diagnostic, i, &mut range) });
+        let range_start = get_source_location_log(unsafe { clang_getRangeStart(range) });
+        let range_end = get_source_location_log(unsafe { clang_getRangeEnd(range) });
+        fixits.push(FixIt { range_start, range_end, replacement });
+    }
+    fixits
 }
 
 // --- VisitorContext for LogIdentifier ---
@@ -52,7 +258,19 @@ struct LogVisitorContext<'ctx> {
     current_file_path: &'ctx Path,
     current_parent_function_qname: Option<String>,
     current_parent_class_name: Option<String>,
-    // translation_unit: CXTranslationUnit, 
+    tu: CXTranslationUnit,
+}
+
+/// Returns `true` if `sites` already has an entry at `loc`. Used to avoid double-recording a
+/// call when it is visible both as a `CXCursor_CallExpr` and as the `CXCursor_MacroExpansion`
+/// that produced it (e.g. a logging macro that expands to a plain function call).
+#[allow(dead_code)]
+fn has_site_at_location(sites: &[LogCallSite], loc: &AstSourceLocation) -> bool {
+    sites.iter().any(|site| {
+        site.source_location.file_path == loc.file_path
+            && site.source_location.line == loc.line
+            && site.source_location.column == loc.column
+    })
 }
 
 // --- Helper Functions ---
@@ -91,20 +309,30 @@ fn get_source_location_log(cx_location: CXSourceLocation) -> AstSourceLocation {
 #[allow(dead_code)]
 pub struct LogIdentifier<'a> {
     config: &'a Config,
+    compilation_database: Option<CXCompilationDatabase>,
+}
+
+impl<'a> Drop for LogIdentifier<'a> {
+    fn drop(&mut self) {
+        if let Some(db) = self.compilation_database {
+            unsafe { clang_CompilationDatabase_dispose(db) };
+        }
+    }
 }
 
 #[allow(dead_code)]
 impl<'a> LogIdentifier<'a> {
     pub fn new(config: &'a Config) -> Self {
         debug!("Initializing LogIdentifier...");
-        LogIdentifier { config }
+        let compilation_database = config.analysis.compile_commands_dir.as_deref().and_then(load_compilation_database);
+        LogIdentifier { config, compilation_database }
     }
 
     pub fn identify_log_calls_in_file(
         &self,
-        file_ast_info: &FileAstInfo, 
+        file_ast_info: &FileAstInfo,
         file_content: &str,
-    ) -> Result<Vec<LogCallSite>, String> {
+    ) -> Result<(Vec<LogCallSite>, Vec<ParseDiagnostic>), String> {
         info!("Identifying log calls in file: {}", file_ast_info.file_path.display());
         let mut log_call_sites = Vec::new();
 
@@ -124,12 +352,12 @@ impl<'a> LogIdentifier<'a> {
                 Length: file_content.len() as std::os::raw::c_ulong,
             };
             
-            let arg1_std_cpp17 = CString::new("-std=c++17").unwrap();
-            let arg2_xcpp = CString::new("-xc++").unwrap();
-            let args_vec: Vec<*const c_char> = vec![
-                arg1_std_cpp17.as_ptr(),
-                arg2_xcpp.as_ptr(),
-            ];
+            let default_args = vec![CString::new("-std=c++17").unwrap(), CString::new("-xc++").unwrap()];
+            let resolved_args = self
+                .compilation_database
+                .and_then(|db| compile_args_for_file(db, &file_ast_info.file_path))
+                .unwrap_or(default_args);
+            let args_vec: Vec<*const c_char> = resolved_args.iter().map(|a| a.as_ptr()).collect();
 
             let tu = clang_parseTranslationUnit(
                 index, c_file_path.as_ptr(), 
@@ -143,53 +371,474 @@ impl<'a> LogIdentifier<'a> {
                 clang_disposeIndex(index);
                 return Err(format!("LogIdentifier: Failed to create TranslationUnit for {}", file_ast_info.file_path.display()));
             }
-            
-            let num_diagnostics = clang_getNumDiagnostics(tu);
-            let mut has_fatal_errors = false;
-            for i in 0..num_diagnostics {
-                let diagnostic = clang_getDiagnostic(tu, i);
-                let diag_string = cxstring_to_string_log(clang_formatDiagnostic(diagnostic, clang_defaultDiagnosticDisplayOptions()));
-                let severity = clang_getDiagnosticSeverity(diagnostic);
-                if severity == CXDiagnostic_Error || severity == CXDiagnostic_Fatal {
-                    error!("LogIdentifier Clang [Error/Fatal] for {}: {}", file_ast_info.file_path.display(), diag_string);
-                    has_fatal_errors = true;
-                } else if severity == CXDiagnostic_Warning {
-                    warn!("LogIdentifier Clang [Warning] for {}: {}", file_ast_info.file_path.display(), diag_string);
-                }
-                clang_disposeDiagnostic(diagnostic);
-            }
+
+            let fatal_threshold = DiagnosticSeverity::threshold_from_config(&self.config.analysis.fatal_diagnostic_severity);
+            let (diagnostics, has_fatal_errors) = collect_parse_diagnostics(tu, &file_ast_info.file_path, fatal_threshold);
             if has_fatal_errors {
                  clang_disposeTranslationUnit(tu);
                  clang_disposeIndex(index);
                  return Err(format!("LogIdentifier: Fatal parsing errors in {}", file_ast_info.file_path.display()));
             }
 
-
-            let cursor = clang_getTranslationUnitCursor(tu);
-            let mut visitor_context = LogVisitorContext {
-                log_call_sites: &mut log_call_sites,
-                config: self.config,
-                current_file_path: &file_ast_info.file_path,
-                current_parent_function_qname: None,
-                current_parent_class_name: None,
-                // translation_unit: tu,
-            };
-
-            clang_visitChildren(
-                cursor,
-                visit_log_identifier_cursor,
-                &mut visitor_context as *mut _ as *mut std::ffi::c_void,
-            );
+            visit_translation_unit_for_log_calls(tu, self.config, file_ast_info, &mut log_call_sites);
 
             clang_disposeTranslationUnit(tu);
             clang_disposeIndex(index);
+
+            info!("Found {} log call sites in {}", log_call_sites.len(), file_ast_info.file_path.display());
+            Ok((log_call_sites, diagnostics))
         }
-        
-        info!("Found {} log call sites in {}", log_call_sites.len(), file_ast_info.file_path.display());
-        Ok(log_call_sites)
     }
 }
 
+/// Loads a clang compilation database from `dir` (a directory containing `compile_commands.json`),
+/// warning and returning `None` on failure so the caller falls back to its hardcoded default
+/// compile args for every file.
+#[allow(dead_code)]
+fn load_compilation_database(dir: &str) -> Option<CXCompilationDatabase> {
+    let c_dir = CString::new(dir.as_bytes()).ok()?;
+    let mut error = CXCompilationDatabase_NoError;
+    let db = unsafe { clang_CompilationDatabase_fromDirectory(c_dir.as_ptr(), &mut error) };
+    if error != CXCompilationDatabase_NoError || db.is_null() {
+        warn!("LogIdentifier: Failed to load compile_commands.json from '{}'; falling back to default compile args for every file.", dir);
+        None
+    } else {
+        info!("LogIdentifier: Loaded compilation database from '{}'.", dir);
+        Some(db)
+    }
+}
+
+/// Looks up the exact compile argument vector clang recorded for `file_path` in `db`, stripping
+/// the positional source path and output flags (`-o`, `-c`) since those are supplied separately
+/// to `clang_parseTranslationUnit`. Returns `None` if no entry exists for this file, in which
+/// case the caller falls back to its hardcoded default args.
+#[allow(dead_code)]
+fn compile_args_for_file(db: CXCompilationDatabase, file_path: &Path) -> Option<Vec<CString>> {
+    let c_file_path = CString::new(file_path.to_string_lossy().as_bytes()).ok()?;
+
+    unsafe {
+        let commands = clang_CompilationDatabase_getCompileCommands(db, c_file_path.as_ptr());
+        if commands.is_null() {
+            return None;
+        }
+        let num_commands = clang_CompileCommands_getSize(commands);
+        if num_commands == 0 {
+            clang_CompileCommands_dispose(commands);
+            return None;
+        }
+
+        let command = clang_CompileCommands_getCommand(commands, 0);
+        let num_args = clang_CompileCommand_getNumArgs(command);
+        let file_path_str = file_path.to_string_lossy().into_owned();
+
+        let mut args = Vec::new();
+        let mut skip_next = false;
+        // Skip index 0: it's the compiler executable itself (e.g. "clang++"), not a flag.
+        for i in 1..num_args {
+            let arg = cxstring_to_string_log(clang_CompileCommand_getArg(command, i as u32));
+            if skip_next {
+                skip_next = false;
+                continue;
+            }
+            if arg == "-o" || arg == "-c" {
+                skip_next = arg == "-o";
+                continue;
+            }
+            if arg == file_path_str {
+                continue;
+            }
+            args.push(arg);
+        }
+
+        clang_CompileCommands_dispose(commands);
+
+        if args.is_empty() {
+            return None;
+        }
+        Some(args.into_iter().filter_map(|a| CString::new(a).ok()).collect())
+    }
+}
+
+/// Runs diagnostics collection for `tu`, logging each one at the appropriate level and
+/// returning the "cooked" `ParseDiagnostic` records alongside whether any diagnostic met or
+/// exceeded `fatal_threshold` (always `false` if `fatal_threshold` is `None`).
+#[allow(dead_code)]
+fn collect_parse_diagnostics(
+    tu: CXTranslationUnit,
+    file_path: &Path,
+    fatal_threshold: Option<DiagnosticSeverity>,
+) -> (Vec<ParseDiagnostic>, bool) {
+    let mut has_fatal_errors = false;
+    let num_diagnostics = unsafe { clang_getNumDiagnostics(tu) };
+    let mut diagnostics = Vec::with_capacity(num_diagnostics as usize);
+    for i in 0..num_diagnostics {
+        let diagnostic = unsafe { clang_getDiagnostic(tu, i) };
+        let diag_string = cxstring_to_string_log(unsafe {
+            clang_formatDiagnostic(diagnostic, clang_defaultDiagnosticDisplayOptions())
+        });
+        let severity = DiagnosticSeverity::from_cx(unsafe { clang_getDiagnosticSeverity(diagnostic) });
+        let location = get_source_location_log(unsafe { clang_getDiagnosticLocation(diagnostic) });
+        let fixits = read_fixits(diagnostic);
+
+        match severity {
+            DiagnosticSeverity::Error | DiagnosticSeverity::Fatal => {
+                error!("LogIdentifier Clang [Error/Fatal] for {}: {}", file_path.display(), diag_string);
+            }
+            DiagnosticSeverity::Warning => {
+                warn!("LogIdentifier Clang [Warning] for {}: {}", file_path.display(), diag_string);
+            }
+            DiagnosticSeverity::Note | DiagnosticSeverity::Ignored => {}
+        }
+        if let Some(threshold) = fatal_threshold {
+            if severity >= threshold {
+                has_fatal_errors = true;
+            }
+        }
+
+        diagnostics.push(ParseDiagnostic { severity, message: diag_string, location, fixits });
+        unsafe { clang_disposeDiagnostic(diagnostic) };
+    }
+    (diagnostics, has_fatal_errors)
+}
+
+/// Caches translation units across files across a long-lived `CXIndex`, so scanning a directory
+/// repeatedly (or re-checking one file after a small edit) doesn't pay a fresh full-parse cost
+/// every time. A cache hit is refreshed via `clang_reparseTranslationUnit` against a precompiled
+/// preamble instead of a full reparse; entries are evicted LRU-style once
+/// `max_cached_translation_units` is exceeded.
+#[allow(dead_code)]
+pub struct LogIdentifierSession<'a> {
+    config: &'a Config,
+    index: CXIndex,
+    cache: HashMap<PathBuf, CXTranslationUnit>,
+    lru_order: VecDeque<PathBuf>,
+    max_cached_translation_units: usize,
+    /// When true, passes `CXTranslationUnit_SkipFunctionBodies` when creating a translation
+    /// unit, so clang skips every function body in it (not only bodies already seen elsewhere in
+    /// the session); log calls inside those bodies are then not found.
+    skip_unchanged_function_bodies: bool,
+    compilation_database: Option<CXCompilationDatabase>,
+}
+
+impl<'a> Drop for LogIdentifierSession<'a> {
+    fn drop(&mut self) {
+        for (_, tu) in self.cache.drain() {
+            unsafe { clang_disposeTranslationUnit(tu) };
+        }
+        unsafe { clang_disposeIndex(self.index) };
+        if let Some(db) = self.compilation_database {
+            unsafe { clang_CompilationDatabase_dispose(db) };
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl<'a> LogIdentifierSession<'a> {
+    const DEFAULT_MAX_CACHED_TRANSLATION_UNITS: usize = 32;
+
+    pub fn new(config: &'a Config) -> Self {
+        debug!("Initializing LogIdentifierSession...");
+        let index = unsafe { clang_createIndex(0, 0) };
+        let compilation_database = config.analysis.compile_commands_dir.as_deref().and_then(load_compilation_database);
+        LogIdentifierSession {
+            config,
+            index,
+            cache: HashMap::new(),
+            lru_order: VecDeque::new(),
+            max_cached_translation_units: Self::DEFAULT_MAX_CACHED_TRANSLATION_UNITS,
+            skip_unchanged_function_bodies: false,
+            compilation_database,
+        }
+    }
+
+    pub fn with_max_cached_translation_units(mut self, max: usize) -> Self {
+        self.max_cached_translation_units = max;
+        self
+    }
+
+    pub fn with_skip_unchanged_function_bodies(mut self, skip: bool) -> Self {
+        self.skip_unchanged_function_bodies = skip;
+        self
+    }
+
+    fn touch(&mut self, path: &Path) {
+        self.lru_order.retain(|p| p != path);
+        self.lru_order.push_back(path.to_path_buf());
+    }
+
+    fn evict_if_needed(&mut self) {
+        while self.cache.len() > self.max_cached_translation_units {
+            match self.lru_order.pop_front() {
+                Some(oldest) => {
+                    if let Some(tu) = self.cache.remove(&oldest) {
+                        unsafe { clang_disposeTranslationUnit(tu) };
+                    }
+                }
+                None => break,
+            }
+        }
+    }
+
+    fn create_translation_unit(
+        &self,
+        c_file_path: &CString,
+        unsaved_file: &CXUnsavedFile,
+        file_path: &Path,
+    ) -> Result<CXTranslationUnit, String> {
+        let default_args = vec![CString::new("-std=c++17").unwrap(), CString::new("-xc++").unwrap()];
+        let resolved_args = self
+            .compilation_database
+            .and_then(|db| compile_args_for_file(db, file_path))
+            .unwrap_or(default_args);
+        let args_vec: Vec<*const c_char> = resolved_args.iter().map(|a| a.as_ptr()).collect();
+
+        let mut options = CXTranslationUnit_DetailedPreprocessingRecord | CXTranslationUnit_CreatePreambleOnFirstParse;
+        if self.skip_unchanged_function_bodies {
+            options |= CXTranslationUnit_SkipFunctionBodies;
+        }
+
+        let tu = unsafe {
+            clang_parseTranslationUnit(
+                self.index,
+                c_file_path.as_ptr(),
+                args_vec.as_ptr(),
+                args_vec.len() as i32,
+                unsaved_file as *const _ as *mut CXUnsavedFile,
+                1,
+                options,
+            )
+        };
+        if tu.is_null() {
+            return Err(format!("LogIdentifierSession: Failed to create TranslationUnit for {}", file_path.display()));
+        }
+        Ok(tu)
+    }
+
+    pub fn identify_log_calls_in_file(
+        &mut self,
+        file_ast_info: &FileAstInfo,
+        file_content: &str,
+    ) -> Result<(Vec<LogCallSite>, Vec<ParseDiagnostic>), String> {
+        info!("Identifying log calls in file (session): {}", file_ast_info.file_path.display());
+        let canonical_path = file_ast_info
+            .file_path
+            .canonicalize()
+            .unwrap_or_else(|_| file_ast_info.file_path.clone());
+
+        let c_file_path_str = file_ast_info.file_path.to_string_lossy();
+        let c_file_path = CString::new(c_file_path_str.as_bytes())
+            .map_err(|e| format!("LogIdentifierSession: Failed to convert file path to CString: {}", e))?;
+        let c_file_content = CString::new(file_content.as_bytes())
+            .map_err(|e| format!("LogIdentifierSession: Failed to convert file content to CString: {}", e))?;
+        let unsaved_file = CXUnsavedFile {
+            Filename: c_file_path.as_ptr(),
+            Contents: c_file_content.as_ptr(),
+            Length: file_content.len() as std::os::raw::c_ulong,
+        };
+
+        let tu = match self.cache.get(&canonical_path).copied() {
+            Some(cached_tu) => {
+                let reparse_failed = unsafe {
+                    clang_reparseTranslationUnit(
+                        cached_tu,
+                        1,
+                        &unsaved_file as *const _ as *mut CXUnsavedFile,
+                        clang_defaultReparseOptions(cached_tu),
+                    )
+                } != 0;
+                if reparse_failed {
+                    warn!(
+                        "LogIdentifierSession: Failed to reparse {}, discarding cached translation unit",
+                        file_ast_info.file_path.display()
+                    );
+                    unsafe { clang_disposeTranslationUnit(cached_tu) };
+                    self.cache.remove(&canonical_path);
+                    self.create_translation_unit(&c_file_path, &unsaved_file, &file_ast_info.file_path)?
+                } else {
+                    cached_tu
+                }
+            }
+            None => self.create_translation_unit(&c_file_path, &unsaved_file, &file_ast_info.file_path)?,
+        };
+
+        self.cache.insert(canonical_path.clone(), tu);
+        self.touch(&canonical_path);
+        self.evict_if_needed();
+
+        let fatal_threshold = DiagnosticSeverity::threshold_from_config(&self.config.analysis.fatal_diagnostic_severity);
+        let (diagnostics, has_fatal_errors) = collect_parse_diagnostics(tu, &file_ast_info.file_path, fatal_threshold);
+        if has_fatal_errors {
+            return Err(format!("LogIdentifierSession: Fatal parsing errors in {}", file_ast_info.file_path.display()));
+        }
+
+        let mut log_call_sites = Vec::new();
+        visit_translation_unit_for_log_calls(tu, self.config, file_ast_info, &mut log_call_sites);
+
+        info!(
+            "Found {} log call sites in {} (session)",
+            log_call_sites.len(),
+            file_ast_info.file_path.display()
+        );
+        Ok((log_call_sites, diagnostics))
+    }
+}
+
+/// Visits `tu`, appending every identified `LogCallSite` to `log_call_sites`.
+#[allow(dead_code)]
+fn visit_translation_unit_for_log_calls(
+    tu: CXTranslationUnit,
+    config: &Config,
+    file_ast_info: &FileAstInfo,
+    log_call_sites: &mut Vec<LogCallSite>,
+) {
+    let cursor = unsafe { clang_getTranslationUnitCursor(tu) };
+    let mut visitor_context = LogVisitorContext {
+        log_call_sites,
+        config,
+        current_file_path: &file_ast_info.file_path,
+        current_parent_function_qname: None,
+        current_parent_class_name: None,
+        tu,
+    };
+
+    unsafe {
+        clang_visitChildren(
+            cursor,
+            visit_log_identifier_cursor,
+            &mut visitor_context as *mut _ as *mut std::ffi::c_void,
+        );
+    }
+}
+
+
+/// Extracts the argument list of a function-like macro expansion (e.g. `LOG_DEBUG("x=%d", x)`)
+/// by tokenizing the expansion's extent and splitting the tokens inside the outer matching
+/// parentheses on top-level commas, and also returns the spelling of the first `CXToken_Literal`
+/// token among them (e.g. the `"value=%d"` in `LOG_INFO(TAG, "value=%d", x)`) — the macro-call
+/// counterpart to `reconstruct_cursor_text_and_literal`'s literal detection, needed because a
+/// macro expansion's arguments aren't themselves cursors `clang_Cursor_getArgument` can walk.
+/// Returns an empty `Vec`/`None` for object-like macros (no `(` token at all), rather than
+/// treating that as an error.
+#[allow(dead_code)]
+fn extract_macro_arguments(cursor: CXCursor, tu: CXTranslationUnit) -> (Vec<String>, Option<String>) {
+    let extent = unsafe { clang_getCursorExtent(cursor) };
+    let mut tokens_ptr = std::ptr::null_mut();
+    let mut num_tokens = 0;
+    unsafe { clang_tokenize(tu, extent, &mut tokens_ptr, &mut num_tokens) };
+
+    if tokens_ptr.is_null() || num_tokens == 0 {
+        return (Vec::new(), None);
+    }
+
+    let tokens = unsafe { std::slice::from_raw_parts(tokens_ptr, num_tokens as usize) };
+    let spellings: Vec<String> = tokens
+        .iter()
+        .map(|token| cxstring_to_string_log(unsafe { clang_getTokenSpelling(tu, *token) }))
+        .collect();
+    let mut first_literal = None;
+    for token in tokens {
+        let token_loc = unsafe { clang_getTokenLocation(tu, *token) };
+        if unsafe { clang_Location_isFromMainFile(token_loc) } == 0 {
+            continue;
+        }
+        if unsafe { clang_getTokenKind(*token) } == CXToken_Literal {
+            first_literal = Some(cxstring_to_string_log(unsafe { clang_getTokenSpelling(tu, *token) }));
+            break;
+        }
+    }
+    unsafe { clang_disposeTokens(tu, tokens_ptr, num_tokens) };
+
+    let open_paren_idx = match spellings.iter().position(|s| s == "(") {
+        Some(idx) => idx,
+        None => return (Vec::new(), first_literal),
+    };
+
+    let mut depth = 0i32;
+    let mut close_paren_idx = None;
+    for (i, spelling) in spellings.iter().enumerate().skip(open_paren_idx) {
+        match spelling.as_str() {
+            "(" => depth += 1,
+            ")" => {
+                depth -= 1;
+                if depth == 0 {
+                    close_paren_idx = Some(i);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let close_paren_idx = match close_paren_idx {
+        Some(idx) => idx,
+        None => return (Vec::new(), first_literal),
+    };
+
+    let mut arguments = Vec::new();
+    let mut current_arg = String::new();
+    let mut paren_depth = 0i32;
+    for spelling in &spellings[open_paren_idx + 1..close_paren_idx] {
+        match spelling.as_str() {
+            "(" => {
+                paren_depth += 1;
+                current_arg.push_str(spelling);
+            }
+            ")" => {
+                paren_depth -= 1;
+                current_arg.push_str(spelling);
+            }
+            "," if paren_depth == 0 => {
+                arguments.push(current_arg.trim().to_string());
+                current_arg = String::new();
+            }
+            _ => {
+                if !current_arg.is_empty() {
+                    current_arg.push(' ');
+                }
+                current_arg.push_str(spelling);
+            }
+        }
+    }
+    if !current_arg.trim().is_empty() {
+        arguments.push(current_arg.trim().to_string());
+    }
+    (arguments, first_literal)
+}
+
+/// Reconstructs the exact source text of `cursor` (e.g. a call argument spanning a `<<` stream
+/// chain or a `QStringLiteral(...)` wrapper) by tokenizing its extent, and returns the spelling
+/// of the first `CXToken_Literal` token encountered, if any. Tokens outside the main file (i.e.
+/// pulled in from a macro expansion) are skipped so macro-expanded text doesn't leak in.
+#[allow(dead_code)]
+fn reconstruct_cursor_text_and_literal(cursor: CXCursor, tu: CXTranslationUnit) -> (String, Option<String>) {
+    let extent = unsafe { clang_getCursorExtent(cursor) };
+    let mut tokens_ptr = std::ptr::null_mut();
+    let mut num_tokens = 0;
+    unsafe { clang_tokenize(tu, extent, &mut tokens_ptr, &mut num_tokens) };
+
+    if tokens_ptr.is_null() || num_tokens == 0 {
+        return (String::new(), None);
+    }
+
+    let tokens = unsafe { std::slice::from_raw_parts(tokens_ptr, num_tokens as usize) };
+    let mut text = String::new();
+    let mut first_literal = None;
+    for token in tokens {
+        let token_loc = unsafe { clang_getTokenLocation(tu, *token) };
+        if unsafe { clang_Location_isFromMainFile(token_loc) } == 0 {
+            continue;
+        }
+        let spelling = cxstring_to_string_log(unsafe { clang_getTokenSpelling(tu, *token) });
+        if first_literal.is_none() && unsafe { clang_getTokenKind(*token) } == CXToken_Literal {
+            first_literal = Some(spelling.clone());
+        }
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(&spelling);
+    }
+    unsafe { clang_disposeTokens(tu, tokens_ptr, num_tokens) };
+    (text, first_literal)
+}
 
 // --- AST Visitor for LogIdentifier ---
 #[allow(non_upper_case_globals)]
@@ -275,26 +924,65 @@ extern "C" fn visit_log_identifier_cursor(
 
                 if matched {
                     let call_loc = get_source_location_log(unsafe { clang_getCursorLocation(cursor) });
-                    
-                    let mut message_arguments = Vec::new();
-                    let num_args = unsafe { clang_Cursor_getNumArguments(cursor) };
-                    for i in 0..num_args {
-                        let arg_cursor = unsafe { clang_Cursor_getArgument(cursor, i as u32) };
-                        let arg_text = cxstring_to_string_log(unsafe { clang_getCursorSpelling(arg_cursor) });
-                        message_arguments.push(if !arg_text.is_empty() { arg_text } else { "<complex_arg>".to_string() });
-                    }
+                    if !has_site_at_location(context.log_call_sites, &call_loc) {
+                        let mut message_arguments = Vec::new();
+                        let mut format_string = None;
+                        let num_args = unsafe { clang_Cursor_getNumArguments(cursor) };
+                        for i in 0..num_args {
+                            let arg_cursor = unsafe { clang_Cursor_getArgument(cursor, i as u32) };
+                            let (arg_text, arg_literal) = reconstruct_cursor_text_and_literal(arg_cursor, context.tu);
+                            if format_string.is_none() {
+                                format_string = arg_literal;
+                            }
+                            message_arguments.push(if !arg_text.is_empty() { arg_text } else { "<complex_arg>".to_string() });
+                        }
 
-                    let log_call = LogCallSite {
-                        function_name: callee_name,
-                        source_location: call_loc,
-                        log_level,
-                        log_type,
-                        parent_function_qualified_name: parent_func_qname.clone(),
-                        containing_class_name: context.current_parent_class_name.clone(),
-                        message_arguments,
+                        let log_call = LogCallSite {
+                            function_name: callee_name,
+                            source_location: call_loc,
+                            log_level,
+                            log_type,
+                            parent_function_qualified_name: parent_func_qname.clone(),
+                            containing_class_name: context.current_parent_class_name.clone(),
+                            message_arguments,
+                            format_string,
+                        };
+                        debug!("Identified log call: {:?}", log_call);
+                        context.log_call_sites.push(log_call);
+                    }
+                }
+            }
+        }
+        CXCursor_MacroExpansion => {
+            if let Some(ref parent_func_qname) = context.current_parent_function_qname {
+                let macro_name = cxstring_to_string_log(unsafe { clang_getCursorSpelling(cursor) });
+                if let Some(level_str) = context.config.log_functions.macros.get(&macro_name) {
+                    let log_level = match level_str.to_lowercase().as_str() {
+                        "debug" => LogLevel::Debug,
+                        "info" => LogLevel::Info,
+                        "warning" | "warn" => LogLevel::Warning,
+                        "error" | "critical" => LogLevel::Critical,
+                        "fatal" => LogLevel::Fatal,
+                        _ => LogLevel::Unknown,
                     };
-                    debug!("Identified log call: {:?}", log_call);
-                    context.log_call_sites.push(log_call);
+
+                    let call_loc = get_source_location_log(unsafe { clang_getCursorLocation(cursor) });
+                    if !has_site_at_location(context.log_call_sites, &call_loc) {
+                        let (message_arguments, format_string) = extract_macro_arguments(cursor, context.tu);
+
+                        let log_call = LogCallSite {
+                            function_name: macro_name,
+                            source_location: call_loc,
+                            log_level,
+                            log_type: LogType::Custom,
+                            parent_function_qualified_name: parent_func_qname.clone(),
+                            containing_class_name: context.current_parent_class_name.clone(),
+                            message_arguments,
+                            format_string,
+                        };
+                        debug!("Identified macro-based log call: {:?}", log_call);
+                        context.log_call_sites.push(log_call);
+                    }
                 }
             }
         }
@@ -2,8 +2,13 @@ pub mod log_identifier;
 
 // Re-export key structs and the identifier itself for easier access
 pub use log_identifier::{
-    LogIdentifier, 
-    LogCallSite, 
-    LogLevel, 
-    LogType
+    LogIdentifier,
+    LogCallSite,
+    LogLevel,
+    LogType,
+    ParseDiagnostic,
+    DiagnosticSeverity,
+    FixIt,
+    LogCallSiteQuery,
+    LogIdentifierSession,
 };
@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// Crate-wide error type threading a stage label through every fallible step of a run, so
+/// `main` can report *which* phase failed and map it to a distinct, stable process exit code
+/// instead of always exiting 0 regardless of outcome.
+#[derive(Error, Debug)]
+pub enum AppError {
+    #[error("configuration error: {0}")]
+    Config(String),
+
+    #[error("failed to collect source files: {0}")]
+    SourceCollection(String),
+
+    #[error("AST analysis failed: {0}")]
+    AstAnalysis(String),
+
+    #[error("log identification failed for '{}': {message}", path.display())]
+    LogIdentification { path: PathBuf, message: String },
+
+    #[error("coverage calculation failed: {0}")]
+    Coverage(String),
+
+    #[error("report generation failed: {0}")]
+    Reporting(String),
+
+    #[error("coverage thresholds not met:\n{}", .0.iter().map(|f| format!("  - {}", f)).collect::<Vec<_>>().join("\n"))]
+    ThresholdNotMet(Vec<String>),
+}
+
+impl AppError {
+    /// The process exit code for this error's stage. Distinct per variant (and disjoint from 0
+    /// and from clap's own exit codes) so calling scripts and CI can tell *why* a run failed
+    /// without scraping stderr.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AppError::Config(_) => 3,
+            AppError::SourceCollection(_) => 4,
+            AppError::AstAnalysis(_) => 5,
+            AppError::LogIdentification { .. } => 6,
+            AppError::Coverage(_) => 7,
+            AppError::Reporting(_) => 8,
+            AppError::ThresholdNotMet(_) => 9,
+        }
+    }
+}
@@ -2,205 +2,230 @@ mod utils;
 mod config;
 mod cli;
 mod source_manager;
-mod core; 
+mod core;
 mod reporter; // Added reporter module
+mod error;
 
 use config::ConfigManager;
-use cli::parse_arguments;
+use cli::{parse_arguments, CliOptions};
 use source_manager::SourceManager;
-use core::ast_analyzer::AstAnalyzer; 
+use core::ast_analyzer::AstAnalyzer;
 use core::log_identifier::LogIdentifier;
-use core::coverage::CoverageCalculator;
+use core::coverage::{check_thresholds, merge as merge_coverage, suppress as suppress_coverage, CoverageCalculator, ProjectCoverage};
 use reporter::get_reporter; // Use the reporter factory
 use utils::log_utils;
-use utils::file_utils as app_file_utils; 
+use utils::file_utils as app_file_utils;
+use error::AppError;
 use std::collections::HashMap;
 use std::path::PathBuf;
-use std::fs::File as StdFile; // Alias to avoid conflict with crate::File if any
-use std::io::{BufWriter, Write}; // For writing reports
-use log::{info, error, debug, warn};
+use std::io::{BufWriter, IsTerminal, Write}; // For writing reports
+use log::{info, error, debug};
 
 fn main() {
-    log_utils::init_logger(); 
-
-    info!("Parsing command line arguments...");
     let cli_options = parse_arguments();
-    info!("Parsed CLI options: {:?}", cli_options);
 
-    if let Some(log_level_str) = &cli_options.log_level {
-        warn!("CLI log level ('{}') specified. For env_logger, this typically requires RUST_LOG to be set *before* application start. Current init_logger initializes on first call.", log_level_str);
-    }
+    let exit_code = match run(cli_options) {
+        Ok(()) => 0,
+        Err(e) => {
+            // `log::error!` is a no-op if the logger never got initialized (e.g. the error
+            // happened while resolving config, before `init_logger` ran), so also print to
+            // stderr directly to guarantee the failure is visible.
+            error!("{}", e);
+            eprintln!("dlogcover-rs: {}", e);
+            e.exit_code()
+        }
+    };
+
+    std::process::exit(exit_code);
+}
 
+/// Runs one end-to-end analysis pass. Each stage's failure is wrapped in the [`AppError`]
+/// variant for that stage before being propagated, so `main` can map it to a distinct exit code.
+fn run(cli_options: CliOptions) -> Result<(), AppError> {
+    // Command-line parsing and config resolution happen before the logger is initialized (its
+    // setup, including the log file path, is itself config-driven), so any `log::*` calls in
+    // that window are silently dropped by the `log` facade's no-op default logger.
+    let config_manager = ConfigManager::new(&cli_options).map_err(|e| AppError::Config(e.to_string()))?;
+    let app_config = &config_manager.config; // Get a reference to the config
+
+    let level_override = match &cli_options.log_level {
+        Some(level_str) => {
+            let level = level_str
+                .parse()
+                .map_err(|e| AppError::Config(format!("invalid log level '{}': {}", level_str, e)))?;
+            Some(level)
+        }
+        None if cli_options.verbose > 0 || cli_options.quiet > 0 => Some(
+            log_utils::level_filter_from_verbosity(cli_options.verbose, cli_options.quiet),
+        ),
+        None => None,
+    };
+
+    log_utils::init_logger(&app_config.logging, level_override).map_err(AppError::Config)?;
+
+    info!("Parsed CLI options: {:?}", cli_options);
     info!("Initializing DLogCover-rs application with parsed arguments.");
+    info!("ConfigManager initialized successfully.");
+    debug!("Final effective config: {:?}", app_config);
+
+    info!("Initializing SourceManager...");
+    let mut source_manager = SourceManager::new(app_config).map_err(AppError::SourceCollection)?;
+    info!("SourceManager initialized successfully.");
+
+    info!("Collecting source files...");
+    source_manager.collect_source_files().map_err(AppError::SourceCollection)?;
+    info!("Successfully collected source files.");
+    let collected_files = source_manager.get_source_files();
+    info!("Number of source files collected: {}", collected_files.len());
+
+    if collected_files.is_empty() {
+        info!("No source files to analyze.");
+        return Ok(());
+    }
 
-    match ConfigManager::new(&cli_options) {
-        Ok(config_manager) => {
-            info!("ConfigManager initialized successfully.");
-            let app_config = &config_manager.config; // Get a reference to the config
-            debug!("Final effective config: {:?}", app_config);
-
-            info!("Initializing SourceManager...");
-            match SourceManager::new(app_config) {
-                Ok(mut source_manager) => {
-                    info!("SourceManager initialized successfully.");
-                    
-                    info!("Collecting source files...");
-                    match source_manager.collect_source_files() {
-                        Ok(()) => {
-                            info!("Successfully collected source files.");
-                            let collected_files = source_manager.get_source_files();
-                            info!("Number of source files collected: {}", collected_files.len());
-                            
-                            if collected_files.is_empty() {
-                                info!("No source files to analyze.");
-                            } else {
-                                // Simplified logging of collected files for brevity
-                                if collected_files.len() <= 5 {
-                                     debug!("Collected files: {:?}", collected_files.iter().map(|sf| sf.absolute_path.display()).collect::<Vec<_>>());
-                                } else {
-                                     debug!("Collected files (first 5): {:?}", collected_files.iter().take(5).map(|sf| sf.absolute_path.display()).collect::<Vec<_>>());
-                                }
-
-
-                                // --- AST Analysis ---
-                                info!("Initializing AstAnalyzer...");
-                                let mut ast_analyzer = AstAnalyzer::new(app_config);
-                                info!("AstAnalyzer initialized. Starting analysis...");
-                                match ast_analyzer.analyze_files(collected_files) {
-                                    Ok(()) => {
-                                        info!("AST analysis completed successfully.");
-                                        let ast_results = ast_analyzer.get_analysis_results();
-                                        info!("Number of files with AST info: {}", ast_results.len());
-                                        
-                                        // --- Log Identification ---
-                                        info!("Initializing LogIdentifier...");
-                                        let log_identifier = LogIdentifier::new(app_config);
-                                        info!("LogIdentifier initialized. Identifying log calls...");
-
-                                        let mut log_sites_map = HashMap::new();
-
-                                        for (file_path, ast_info_ref) in ast_results { 
-                                            debug!("Processing file for log identification: {}", file_path.display());
-                                            match app_file_utils::read_file(file_path) {
-                                                Ok(file_content) => {
-                                                    match log_identifier.identify_log_calls_in_file(ast_info_ref, &file_content) {
-                                                        Ok(log_calls) => {
-                                                            info!("Found {} log calls in {}", log_calls.len(), file_path.display());
-                                                            log_sites_map.insert(file_path.clone(), log_calls);
-                                                        }
-                                                        Err(e) => {
-                                                            error!("Failed to identify log calls in {}: {}", file_path.display(), e);
-                                                        }
-                                                    }
-                                                }
-                                                Err(e) => {
-                                                    error!("Failed to read file content for {}: {}", file_path.display(), e);
-                                                }
-                                            }
-                                        }
-
-                                        // --- Coverage Calculation ---
-                                        if !ast_results.is_empty() {
-                                            info!("Initializing CoverageCalculator...");
-                                            let coverage_calculator = CoverageCalculator::new(app_config);
-                                            info!("CoverageCalculator initialized. Calculating project coverage...");
-
-                                            match coverage_calculator.calculate_project_coverage(ast_results, &log_sites_map) {
-                                                Ok(project_coverage) => {
-                                                    info!("Project coverage calculated successfully.");
-                                                    debug!("Project Coverage Details: {:?}", project_coverage);
-                                                    
-                                                    // --- Report Generation ---
-                                                    info!("Generating report (format: {})...", app_config.report.format);
-                                                    if let Some(reporter) = get_reporter(&app_config.report.format) {
-                                                        let mut writer: Box<dyn Write> = match &cli_options.output {
-                                                            Some(output_path_str) => {
-                                                                let output_path = PathBuf::from(output_path_str);
-                                                                info!("Report will be written to: {}", output_path.display());
-                                                                // Ensure parent directory exists
-                                                                if let Some(parent_dir) = output_path.parent() {
-                                                                    if !parent_dir.exists() {
-                                                                        if let Err(e) = std::fs::create_dir_all(parent_dir) {
-                                                                            error!("Failed to create parent directory for report '{}': {}", parent_dir.display(), e);
-                                                                            // Fallback to stdout or error out? For now, error out.
-                                                                            // Consider making this behavior configurable or more robust.
-                                                                            Box::new(std::io::sink()) // Fallback to sink on error
-                                                                        } else {
-                                                                             match StdFile::create(output_path) {
-                                                                                Ok(file) => Box::new(BufWriter::new(file)),
-                                                                                Err(e) => {
-                                                                                    error!("Failed to create report file '{}': {}. Falling back to stdout.", output_path_str, e);
-                                                                                    Box::new(BufWriter::new(std::io::stdout()))
-                                                                                }
-                                                                            }
-                                                                        }
-                                                                    } else {
-                                                                         match StdFile::create(output_path) {
-                                                                            Ok(file) => Box::new(BufWriter::new(file)),
-                                                                            Err(e) => {
-                                                                                error!("Failed to create report file '{}': {}. Falling back to stdout.", output_path_str, e);
-                                                                                Box::new(BufWriter::new(std::io::stdout()))
-                                                                            }
-                                                                        }
-                                                                    }
-                                                                } else { // No parent dir (e.g. "report.txt" in current dir)
-                                                                    match StdFile::create(output_path) {
-                                                                        Ok(file) => Box::new(BufWriter::new(file)),
-                                                                        Err(e) => {
-                                                                            error!("Failed to create report file '{}': {}. Falling back to stdout.", output_path_str, e);
-                                                                            Box::new(BufWriter::new(std::io::stdout()))
-                                                                        }
-                                                                    }
-                                                                }
-                                                            }
-                                                            None => {
-                                                                info!("Report will be written to stdout.");
-                                                                Box::new(BufWriter::new(std::io::stdout()))
-                                                            }
-                                                        };
-
-                                                        if let Err(e) = reporter.generate_report(&project_coverage, &mut writer) {
-                                                            error!("Failed to generate report: {}", e);
-                                                        } else {
-                                                            info!("Report generated successfully.");
-                                                            // Ensure buffer is flushed if writer is BufWriter
-                                                            if let Err(e) = writer.flush() {
-                                                                error!("Failed to flush report writer: {}", e);
-                                                            }
-                                                        }
-                                                    } else {
-                                                        error!("Unsupported report format: {}", app_config.report.format);
-                                                    }
-
-                                                }
-                                                Err(e) => {
-                                                    error!("Failed to calculate project coverage: {}", e);
-                                                }
-                                            }
-                                        } else {
-                                            info!("Skipping coverage calculation and reporting as no AST results were available.");
-                                        }
-                                    }
-                                    Err(e) => {
-                                        error!("AST analysis failed: {}", e);
-                                    }
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            error!("Failed to collect source files: {}", e);
+    // Simplified logging of collected files for brevity
+    if collected_files.len() <= 5 {
+        debug!("Collected files: {:?}", collected_files.iter().map(|sf| sf.absolute_path.display()).collect::<Vec<_>>());
+    } else {
+        debug!("Collected files (first 5): {:?}", collected_files.iter().take(5).map(|sf| sf.absolute_path.display()).collect::<Vec<_>>());
+    }
+
+    // --- AST Analysis ---
+    info!("Initializing AstAnalyzer...");
+    let mut ast_analyzer = AstAnalyzer::new(app_config).map_err(AppError::AstAnalysis)?;
+    info!("AstAnalyzer initialized. Starting analysis...");
+    ast_analyzer.analyze_files(collected_files).map_err(AppError::AstAnalysis)?;
+    info!("AST analysis completed successfully.");
+    let ast_results = ast_analyzer.get_analysis_results();
+    info!("Number of files with AST info: {}", ast_results.len());
+
+    // --- Log Identification ---
+    info!("Initializing LogIdentifier...");
+    let log_identifier = LogIdentifier::new(app_config);
+    info!("LogIdentifier initialized. Identifying log calls...");
+
+    let mut log_sites_map = HashMap::new();
+
+    for (file_path, ast_info_ref) in ast_results {
+        debug!("Processing file for log identification: {}", file_path.display());
+        match app_file_utils::read_file(file_path) {
+            Ok(file_content) => {
+                match log_identifier.identify_log_calls_in_file(ast_info_ref, &file_content) {
+                    Ok((log_calls, diagnostics)) => {
+                        info!("Found {} log calls in {}", log_calls.len(), file_path.display());
+                        if !diagnostics.is_empty() {
+                            debug!("{} parse diagnostics for {}: {:?}", diagnostics.len(), file_path.display(), diagnostics);
                         }
+                        log_sites_map.insert(file_path.clone(), log_calls);
+                    }
+                    Err(e) => {
+                        // A single file's log identification failing doesn't abort the whole
+                        // run; the other files' coverage is still useful, so log and move on.
+                        error!(
+                            "{}",
+                            AppError::LogIdentification { path: file_path.clone(), message: e }
+                        );
                     }
                 }
-                Err(e) => {
-                    error!("Failed to initialize SourceManager: {}", e);
+            }
+            Err(e) => {
+                error!("Failed to read file content for {}: {}", file_path.display(), e);
+            }
+        }
+    }
+
+    // --- Coverage Calculation ---
+    if ast_results.is_empty() {
+        info!("Skipping coverage calculation and reporting as no AST results were available.");
+        return Ok(());
+    }
+
+    info!("Initializing CoverageCalculator...");
+    let coverage_calculator = CoverageCalculator::new(app_config);
+    info!("CoverageCalculator initialized. Calculating project coverage...");
+    let project_coverage = coverage_calculator
+        .calculate_project_coverage(ast_results, &log_sites_map)
+        .map_err(AppError::Coverage)?;
+    info!("Project coverage calculated successfully.");
+    debug!("Project Coverage Details: {:?}", project_coverage);
+
+    let project_coverage = if cli_options.merge_with.is_empty() {
+        project_coverage
+    } else {
+        info!("Merging current coverage with {} prior report(s)...", cli_options.merge_with.len());
+        let mut runs = vec![project_coverage];
+        for report_path in &cli_options.merge_with {
+            let report_json = std::fs::read_to_string(report_path).map_err(|e| {
+                AppError::Coverage(format!("failed to read merge input '{}': {}", report_path, e))
+            })?;
+            let prior_run: ProjectCoverage = serde_json::from_str(&report_json).map_err(|e| {
+                AppError::Coverage(format!("failed to parse merge input '{}' as a JSON coverage report: {}", report_path, e))
+            })?;
+            runs.push(prior_run);
+        }
+        let merged = merge_coverage(&runs).map_err(AppError::Coverage)?;
+        info!("Merge finished: {} file(s) in the combined result.", merged.files.len());
+        merged
+    };
+
+    let mut project_coverage = project_coverage;
+    suppress_coverage(&mut project_coverage, &app_config.suppression);
+
+    // --- Report Generation ---
+    info!("Generating report (format: {})...", app_config.report.format);
+    let is_terminal = cli_options.output.is_none() && std::io::stdout().is_terminal();
+    let use_color = app_config.report.color.unwrap_or(is_terminal);
+    let reporter = get_reporter(&app_config.report.format, use_color).ok_or_else(|| {
+        AppError::Reporting(format!("unsupported report format: {}", app_config.report.format))
+    })?;
+
+    match &cli_options.output {
+        Some(output_path_str) => {
+            let output_path = PathBuf::from(output_path_str);
+            info!("Report will be written to: {}", output_path.display());
+            if let Some(parent_dir) = output_path.parent() {
+                if !parent_dir.as_os_str().is_empty() && !parent_dir.exists() {
+                    std::fs::create_dir_all(parent_dir).map_err(|e| {
+                        AppError::Reporting(format!(
+                            "failed to create parent directory '{}' for report '{}': {}",
+                            parent_dir.display(),
+                            output_path_str,
+                            e
+                        ))
+                    })?;
                 }
             }
+
+            // Rendered in memory first so the report is written atomically: a crash or kill
+            // mid-write can never leave a truncated file at `output_path`, only the previous
+            // content or the complete new report.
+            let mut buffer: Vec<u8> = Vec::new();
+            reporter
+                .generate_report(&project_coverage, &mut buffer)
+                .map_err(|e| AppError::Reporting(format!("failed to generate report: {}", e)))?;
+            let report_content = String::from_utf8(buffer).map_err(|e| {
+                AppError::Reporting(format!("generated report is not valid UTF-8: {}", e))
+            })?;
+            app_file_utils::write_file_atomic(&output_path, &report_content).map_err(|e| {
+                AppError::Reporting(format!("failed to write report file '{}': {}", output_path_str, e))
+            })?;
         }
-        Err(e) => {
-            error!("Failed to initialize ConfigManager: {}", e);
+        None => {
+            info!("Report will be written to stdout.");
+            let mut writer = BufWriter::new(std::io::stdout());
+            reporter
+                .generate_report(&project_coverage, &mut writer)
+                .map_err(|e| AppError::Reporting(format!("failed to generate report: {}", e)))?;
+            writer
+                .flush()
+                .map_err(|e| AppError::Reporting(format!("failed to flush report writer: {}", e)))?;
         }
     }
+    info!("Report generated successfully.");
+
+    info!("Checking coverage against configured thresholds...");
+    check_thresholds(&project_coverage, &app_config.report).map_err(AppError::ThresholdNotMet)?;
 
-    info!("DLogCover-rs processing finished."); 
+    info!("DLogCover-rs processing finished.");
+    Ok(())
 }
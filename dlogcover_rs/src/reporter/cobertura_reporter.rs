@@ -0,0 +1,149 @@
+use super::reporter_strategy::ReporterStrategy;
+use crate::core::coverage::{PerFileCoverage, ProjectCoverage};
+use std::io::{Error as IoError, Write};
+
+/// Writes coverage as a Cobertura-compatible XML tracefile, for CI systems (e.g. Jenkins,
+/// GitLab) that render coverage dashboards from that format rather than LCOV.
+#[allow(dead_code)]
+pub struct CoberturaReporter;
+
+impl CoberturaReporter {
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    fn rate(metrics_percentage: f64) -> f64 {
+        metrics_percentage / 100.0
+    }
+
+    fn write_class(
+        file_coverage: &PerFileCoverage,
+        writer: &mut dyn Write,
+    ) -> Result<(), IoError> {
+        let filename = Self::escape(&file_coverage.file_path.display().to_string());
+        writeln!(
+            writer,
+            "      <class name=\"{name}\" filename=\"{name}\" line-rate=\"{line_rate:.4}\" branch-rate=\"{branch_rate:.4}\" complexity=\"0\">",
+            name = filename,
+            line_rate = Self::rate(file_coverage.overall.percentage),
+            branch_rate = Self::rate(file_coverage.branches.percentage),
+        )?;
+
+        writeln!(writer, "        <methods>")?;
+        // Map every `FunctionInfo`, covered or not, to a `<method>`; line-rate/hits reflect
+        // whether that function actually had a log call.
+        for func_info in &file_coverage.covered_functions {
+            writeln!(
+                writer,
+                "          <method name=\"{name}\" signature=\"\" line-rate=\"1.0000\" branch-rate=\"0.0000\">",
+                name = Self::escape(&func_info.qualified_name),
+            )?;
+            writeln!(writer, "            <lines>")?;
+            writeln!(
+                writer,
+                "              <line number=\"{}\" hits=\"1\"/>",
+                func_info.start_location.line
+            )?;
+            writeln!(writer, "            </lines>")?;
+            writeln!(writer, "          </method>")?;
+        }
+        for func_info in &file_coverage.uncovered_functions {
+            writeln!(
+                writer,
+                "          <method name=\"{name}\" signature=\"\" line-rate=\"0.0000\" branch-rate=\"0.0000\">",
+                name = Self::escape(&func_info.qualified_name),
+            )?;
+            writeln!(writer, "            <lines>")?;
+            writeln!(
+                writer,
+                "              <line number=\"{}\" hits=\"0\"/>",
+                func_info.start_location.line
+            )?;
+            writeln!(writer, "            </lines>")?;
+            writeln!(writer, "          </method>")?;
+        }
+        writeln!(writer, "        </methods>")?;
+
+        writeln!(writer, "        <lines>")?;
+        for func_info in &file_coverage.covered_functions {
+            writeln!(
+                writer,
+                "          <line number=\"{}\" hits=\"1\" branch=\"false\"/>",
+                func_info.start_location.line
+            )?;
+        }
+        for func_info in &file_coverage.uncovered_functions {
+            writeln!(
+                writer,
+                "          <line number=\"{}\" hits=\"0\" branch=\"false\"/>",
+                func_info.start_location.line
+            )?;
+        }
+        for branch_info in &file_coverage.covered_branches {
+            writeln!(
+                writer,
+                "          <line number=\"{}\" hits=\"1\" branch=\"true\" condition-coverage=\"100% (1/1)\"/>",
+                branch_info.start_location.line
+            )?;
+        }
+        for branch_info in &file_coverage.uncovered_branches {
+            writeln!(
+                writer,
+                "          <line number=\"{}\" hits=\"0\" branch=\"true\" condition-coverage=\"0% (0/1)\"/>",
+                branch_info.start_location.line
+            )?;
+        }
+        writeln!(writer, "        </lines>")?;
+
+        writeln!(writer, "      </class>")?;
+        Ok(())
+    }
+}
+
+impl ReporterStrategy for CoberturaReporter {
+    fn generate_report(
+        &self,
+        project_coverage: &ProjectCoverage,
+        writer: &mut dyn Write,
+    ) -> Result<(), IoError> {
+        writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+        writeln!(
+            writer,
+            "<!DOCTYPE coverage SYSTEM \"http://cobertura.sourceforge.net/xml/coverage-04.dtd\">"
+        )?;
+        writeln!(
+            writer,
+            "<coverage line-rate=\"{line_rate:.4}\" branch-rate=\"{branch_rate:.4}\" lines-covered=\"{lines_covered}\" lines-valid=\"{lines_valid}\" branches-covered=\"{branches_covered}\" branches-valid=\"{branches_valid}\" complexity=\"0\" version=\"1.0\">",
+            line_rate = Self::rate(project_coverage.project_overall.percentage),
+            branch_rate = Self::rate(project_coverage.total_branches.percentage),
+            lines_covered = project_coverage.total_functions.covered,
+            lines_valid = project_coverage.total_functions.total,
+            branches_covered = project_coverage.total_branches.covered,
+            branches_valid = project_coverage.total_branches.total,
+        )?;
+        writeln!(writer, "  <sources>")?;
+        writeln!(writer, "    <source>.</source>")?;
+        writeln!(writer, "  </sources>")?;
+
+        writeln!(writer, "  <packages>")?;
+        writeln!(
+            writer,
+            "    <package name=\"dlogcover\" line-rate=\"{line_rate:.4}\" branch-rate=\"{branch_rate:.4}\" complexity=\"0\">",
+            line_rate = Self::rate(project_coverage.project_overall.percentage),
+            branch_rate = Self::rate(project_coverage.total_branches.percentage),
+        )?;
+        writeln!(writer, "      <classes>")?;
+        for file_coverage in &project_coverage.files {
+            Self::write_class(file_coverage, writer)?;
+        }
+        writeln!(writer, "      </classes>")?;
+        writeln!(writer, "    </package>")?;
+        writeln!(writer, "  </packages>")?;
+
+        writeln!(writer, "</coverage>")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+use super::reporter_strategy::ReporterStrategy;
+use crate::core::coverage::{PerFileCoverage, ProjectCoverage};
+use crate::utils::file_utils;
+use log::warn;
+use std::io::{Error as IoError, Write};
+
+#[allow(dead_code)]
+pub struct HtmlReporter;
+
+impl HtmlReporter {
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    fn file_anchor(file_coverage: &PerFileCoverage) -> String {
+        Self::escape(&file_coverage.file_path.display().to_string())
+            .replace(|c: char| !c.is_alphanumeric(), "_")
+    }
+
+    fn write_index(
+        project_coverage: &ProjectCoverage,
+        writer: &mut dyn Write,
+    ) -> Result<(), IoError> {
+        writeln!(writer, "<h1>DLogCover-rs Coverage Report</h1>")?;
+        writeln!(writer, "<table class=\"index\">")?;
+        writeln!(
+            writer,
+            "<tr><th>File</th><th>Functions</th><th>Branches</th><th>Exceptions</th><th>Overall</th></tr>"
+        )?;
+        for file_coverage in &project_coverage.files {
+            writeln!(
+                writer,
+                "<tr><td><a href=\"#{anchor}\">{name}</a></td>{}{}{}{}</tr>",
+                Self::metric_cell(file_coverage.functions.percentage),
+                Self::metric_cell(file_coverage.branches.percentage),
+                Self::metric_cell(file_coverage.exceptions.percentage),
+                Self::metric_cell(file_coverage.overall.percentage),
+                anchor = Self::file_anchor(file_coverage),
+                name = Self::escape(&file_coverage.file_path.display().to_string()),
+            )?;
+        }
+        writeln!(writer, "</table>")?;
+        Ok(())
+    }
+
+    fn metric_cell(percentage: f64) -> String {
+        format!(
+            "<td><div class=\"bar\"><div class=\"bar-fill\" style=\"width:{pct:.2}%\"></div></div> {pct:.2}%</td>",
+            pct = percentage
+        )
+    }
+
+    // Only uncovered functions/branches/exceptions get a (red, "missing log") highlight; covered
+    // items are rendered without any highlight.
+    fn collect_highlights(file_coverage: &PerFileCoverage) -> Vec<(u32, u32, String)> {
+        let mut highlights = Vec::new();
+
+        for func_info in &file_coverage.uncovered_functions {
+            highlights.push((
+                func_info.start_location.line,
+                func_info.end_location.line,
+                format!("function '{}' has no log call", func_info.qualified_name),
+            ));
+        }
+        for branch_info in &file_coverage.uncovered_branches {
+            highlights.push((
+                branch_info.start_location.line,
+                branch_info.end_location.line,
+                format!("{} branch has no log call", branch_info.branch_type),
+            ));
+        }
+        for exc_info in &file_coverage.uncovered_exceptions {
+            highlights.push((
+                exc_info.start_location.line,
+                exc_info.end_location.line,
+                format!("{} block has no log call", exc_info.exception_type),
+            ));
+        }
+
+        highlights
+    }
+
+    fn write_file_page(
+        file_coverage: &PerFileCoverage,
+        writer: &mut dyn Write,
+    ) -> Result<(), IoError> {
+        writeln!(
+            writer,
+            "<h2 id=\"{anchor}\">{name}</h2>",
+            anchor = Self::file_anchor(file_coverage),
+            name = Self::escape(&file_coverage.file_path.display().to_string()),
+        )?;
+
+        let source = match file_utils::read_file(&file_coverage.file_path) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!(
+                    "HtmlReporter: could not read source '{}' for annotation: {}",
+                    file_coverage.file_path.display(),
+                    e
+                );
+                writeln!(writer, "<p><em>Source unavailable: {}</em></p>", Self::escape(&e.to_string()))?;
+                return Ok(());
+            }
+        };
+
+        let highlights = Self::collect_highlights(file_coverage);
+
+        writeln!(writer, "<pre class=\"source\">")?;
+        for (idx, line_text) in source.lines().enumerate() {
+            let line_no = (idx + 1) as u32;
+            let tooltip = highlights
+                .iter()
+                .find(|(start, end, _)| line_no >= *start && line_no <= *end)
+                .map(|(_, _, tooltip)| tooltip.as_str());
+
+            if let Some(tooltip) = tooltip {
+                writeln!(
+                    writer,
+                    "<span class=\"line uncovered\" title=\"{tooltip}\">{line_no:>5} | {text}</span>",
+                    tooltip = Self::escape(tooltip),
+                    line_no = line_no,
+                    text = Self::escape(line_text),
+                )?;
+            } else {
+                writeln!(writer, "<span class=\"line\">{:>5} | {}</span>", line_no, Self::escape(line_text))?;
+            }
+        }
+        writeln!(writer, "</pre>")?;
+        Ok(())
+    }
+}
+
+impl ReporterStrategy for HtmlReporter {
+    fn generate_report(
+        &self,
+        project_coverage: &ProjectCoverage,
+        writer: &mut dyn Write,
+    ) -> Result<(), IoError> {
+        writeln!(writer, "<!DOCTYPE html>")?;
+        writeln!(writer, "<html><head><meta charset=\"utf-8\"><title>DLogCover-rs Coverage Report</title>")?;
+        writeln!(
+            writer,
+            "<style>
+            body {{ font-family: sans-serif; }}
+            table.index {{ border-collapse: collapse; }}
+            table.index td, table.index th {{ border: 1px solid #ccc; padding: 4px 8px; }}
+            .bar {{ display: inline-block; width: 80px; height: 8px; background: #eee; }}
+            .bar-fill {{ height: 8px; background: #4caf50; }}
+            pre.source {{ background: #f7f7f7; padding: 8px; overflow-x: auto; }}
+            .line {{ display: block; white-space: pre; }}
+            .line.uncovered {{ background: #fdd; }}
+            .line.covered {{ background: #dfd; }}
+            </style>"
+        )?;
+        writeln!(writer, "</head><body>")?;
+
+        Self::write_index(project_coverage, writer)?;
+        for file_coverage in &project_coverage.files {
+            Self::write_file_page(file_coverage, writer)?;
+        }
+
+        writeln!(writer, "</body></html>")?;
+        Ok(())
+    }
+}
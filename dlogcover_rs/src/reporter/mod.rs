@@ -1,16 +1,31 @@
 pub mod reporter_strategy;
 pub mod text_reporter;
 pub mod json_reporter;
+pub mod lcov_reporter;
+pub mod summary_reporter;
+pub mod html_reporter;
+pub mod cobertura_reporter;
 
 pub use reporter_strategy::ReporterStrategy;
 use text_reporter::TextReporter;
 use json_reporter::JsonReporter;
+use lcov_reporter::LcovReporter;
+use summary_reporter::SummaryReporter;
+use html_reporter::HtmlReporter;
+use cobertura_reporter::CoberturaReporter;
 
+/// `use_color` affects `TextReporter` and `SummaryReporter`; every other format ignores it. Pass
+/// the final decision: `ReportConfig::color`'s override if set, else whether the destination is
+/// an interactive terminal (`main` resolves this before calling here).
 #[allow(dead_code)] // Will be used by main.rs
-pub fn get_reporter(format: &str) -> Option<Box<dyn ReporterStrategy>> {
+pub fn get_reporter(format: &str, use_color: bool) -> Option<Box<dyn ReporterStrategy>> {
     match format.to_lowercase().as_str() {
-        "text" => Some(Box::new(TextReporter)),
+        "text" => Some(Box::new(TextReporter::new(use_color))),
         "json" => Some(Box::new(JsonReporter)),
+        "lcov" => Some(Box::new(LcovReporter)),
+        "summary" => Some(Box::new(SummaryReporter::new(use_color))),
+        "html" => Some(Box::new(HtmlReporter)),
+        "cobertura" => Some(Box::new(CoberturaReporter)),
         _ => None,
     }
 }
@@ -0,0 +1,105 @@
+use super::reporter_strategy::ReporterStrategy;
+use crate::core::coverage::{CoverageMetrics, PerFileCoverage, ProjectCoverage};
+use std::io::{Error as IoError, Write};
+
+#[allow(dead_code)]
+pub struct SummaryReporter {
+    color: bool,
+}
+
+const COLOR_RED: &str = "\x1b[31m";
+const COLOR_YELLOW: &str = "\x1b[33m";
+const COLOR_GREEN: &str = "\x1b[32m";
+const COLOR_RESET: &str = "\x1b[0m";
+
+const THRESHOLD_RED: f64 = 50.0;
+const THRESHOLD_YELLOW: f64 = 80.0;
+
+impl SummaryReporter {
+    pub fn new(color: bool) -> Self {
+        SummaryReporter { color }
+    }
+
+    fn colorize(percentage: f64) -> &'static str {
+        if percentage < THRESHOLD_RED {
+            COLOR_RED
+        } else if percentage < THRESHOLD_YELLOW {
+            COLOR_YELLOW
+        } else {
+            COLOR_GREEN
+        }
+    }
+
+    fn format_cell(percentage: f64, colorize: bool) -> String {
+        let text = format!("{:>6.2}%", percentage);
+        if colorize {
+            format!("{}{}{}", Self::colorize(percentage), text, COLOR_RESET)
+        } else {
+            text
+        }
+    }
+
+    fn write_row(
+        writer: &mut dyn Write,
+        name: &str,
+        functions: &CoverageMetrics,
+        branches: &CoverageMetrics,
+        exceptions: &CoverageMetrics,
+        overall: &CoverageMetrics,
+        colorize: bool,
+    ) -> Result<(), IoError> {
+        writeln!(
+            writer,
+            "{:<40} {} {} {} {}",
+            name,
+            Self::format_cell(functions.percentage, colorize),
+            Self::format_cell(branches.percentage, colorize),
+            Self::format_cell(exceptions.percentage, colorize),
+            Self::format_cell(overall.percentage, colorize),
+        )
+    }
+}
+
+impl ReporterStrategy for SummaryReporter {
+    fn generate_report(
+        &self,
+        project_coverage: &ProjectCoverage,
+        writer: &mut dyn Write,
+    ) -> Result<(), IoError> {
+        let colorize = self.color;
+
+        writeln!(
+            writer,
+            "{:<40} {:>7} {:>7} {:>7} {:>7}",
+            "File", "Func %", "Branch %", "Excpt %", "Overall %"
+        )?;
+
+        let mut files: Vec<&PerFileCoverage> = project_coverage.files.iter().collect();
+        files.sort_by(|a, b| a.overall.percentage.partial_cmp(&b.overall.percentage).unwrap());
+
+        for file_coverage in &files {
+            let name = file_coverage.file_path.display().to_string();
+            Self::write_row(
+                writer,
+                &name,
+                &file_coverage.functions,
+                &file_coverage.branches,
+                &file_coverage.exceptions,
+                &file_coverage.overall,
+                colorize,
+            )?;
+        }
+
+        Self::write_row(
+            writer,
+            "All files",
+            &project_coverage.total_functions,
+            &project_coverage.total_branches,
+            &project_coverage.total_exceptions,
+            &project_coverage.project_overall,
+            colorize,
+        )?;
+
+        Ok(())
+    }
+}
@@ -3,10 +3,22 @@ use crate::core::coverage::{ProjectCoverage, PerFileCoverage, CoverageMetrics};
 use crate::core::ast_analyzer::{SourceLocation, FunctionInfo, BranchInfo, ExceptionInfo};
 use std::io::{Write, Error as IoError, ErrorKind as IoErrorKind};
 
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const RED: &str = "\x1b[31m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
 #[allow(dead_code)] // Will be used by the factory
-pub struct TextReporter;
+pub struct TextReporter {
+    color: bool,
+}
 
 impl TextReporter {
+    pub fn new(color: bool) -> Self {
+        TextReporter { color }
+    }
+
     fn format_source_location(loc: &SourceLocation) -> String {
         // Assuming SourceLocation.file_path is the full absolute path.
         // For text reports, often just the line and column are needed if file context is already given.
@@ -21,6 +33,85 @@ impl TextReporter {
             item_name, metrics.covered, metrics.total, metrics.percentage
         )
     }
+
+    /// Green at/above 80%, yellow at/above 50%, red below. Wraps `text` in the matching ANSI
+    /// color code, or returns it unchanged when color is disabled.
+    fn colorize_percentage(&self, percentage: f64, text: &str) -> String {
+        if !self.color {
+            return text.to_string();
+        }
+        let color = if percentage >= 80.0 {
+            GREEN
+        } else if percentage >= 50.0 {
+            YELLOW
+        } else {
+            RED
+        };
+        format!("{}{}{}", color, text, RESET)
+    }
+
+    fn bold(&self, text: &str) -> String {
+        if self.color {
+            format!("{}{}{}", BOLD, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn write_summary_table(
+        &self,
+        project_coverage: &ProjectCoverage,
+        writer: &mut dyn Write,
+    ) -> Result<(), IoError> {
+        let mut files: Vec<&PerFileCoverage> = project_coverage.files.iter().collect();
+        files.sort_by(|a, b| {
+            a.overall
+                .percentage
+                .partial_cmp(&b.overall.percentage)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let name_width = files
+            .iter()
+            .map(|f| f.file_path.display().to_string().len())
+            .max()
+            .unwrap_or(4)
+            .max("File".len());
+
+        writeln!(writer, "{}", self.bold("Worst-covered files:"))?;
+        writeln!(
+            writer,
+            "  {:<name_width$}  {:>9}  {:>9}  {:>9}  {:>9}",
+            "File", "Functions", "Branches", "Exceptions", "Overall", name_width = name_width
+        )?;
+        for file_coverage in &files {
+            let name = file_coverage.file_path.display().to_string();
+            writeln!(
+                writer,
+                "  {:<name_width$}  {:>9}  {:>9}  {:>9}  {:>9}",
+                name,
+                self.colorize_percentage(
+                    file_coverage.functions.percentage,
+                    &format!("{:.2}%", file_coverage.functions.percentage)
+                ),
+                self.colorize_percentage(
+                    file_coverage.branches.percentage,
+                    &format!("{:.2}%", file_coverage.branches.percentage)
+                ),
+                self.colorize_percentage(
+                    file_coverage.exceptions.percentage,
+                    &format!("{:.2}%", file_coverage.exceptions.percentage)
+                ),
+                self.colorize_percentage(
+                    file_coverage.overall.percentage,
+                    &format!("{:.2}%", file_coverage.overall.percentage)
+                ),
+                name_width = name_width
+            )?;
+        }
+        writeln!(writer)?;
+        Ok(())
+    }
 }
 
 impl ReporterStrategy for TextReporter {
@@ -33,11 +124,39 @@ impl ReporterStrategy for TextReporter {
         writeln!(writer, "")?;
 
         // Project Overall Summary
-        writeln!(writer, "Project Coverage Summary:")?;
-        writeln!(writer, "  {}", Self::format_coverage_metrics(&project_coverage.total_functions, "Functions"))?;
-        writeln!(writer, "  {}", Self::format_coverage_metrics(&project_coverage.total_branches, "Branches"))?;
-        writeln!(writer, "  {}", Self::format_coverage_metrics(&project_coverage.total_exceptions, "Exceptions"))?;
-        writeln!(writer, "  {}", Self::format_coverage_metrics(&project_coverage.project_overall, "Overall"))?;
+        writeln!(writer, "{}", self.bold("Project Coverage Summary:"))?;
+        writeln!(
+            writer,
+            "  {}",
+            self.colorize_percentage(
+                project_coverage.total_functions.percentage,
+                &Self::format_coverage_metrics(&project_coverage.total_functions, "Functions")
+            )
+        )?;
+        writeln!(
+            writer,
+            "  {}",
+            self.colorize_percentage(
+                project_coverage.total_branches.percentage,
+                &Self::format_coverage_metrics(&project_coverage.total_branches, "Branches")
+            )
+        )?;
+        writeln!(
+            writer,
+            "  {}",
+            self.colorize_percentage(
+                project_coverage.total_exceptions.percentage,
+                &Self::format_coverage_metrics(&project_coverage.total_exceptions, "Exceptions")
+            )
+        )?;
+        writeln!(
+            writer,
+            "  {}",
+            self.colorize_percentage(
+                project_coverage.project_overall.percentage,
+                &Self::format_coverage_metrics(&project_coverage.project_overall, "Overall")
+            )
+        )?;
         writeln!(writer, "")?;
 
         if project_coverage.files.is_empty() {
@@ -45,16 +164,18 @@ impl ReporterStrategy for TextReporter {
             return Ok(());
         }
 
+        self.write_summary_table(project_coverage, writer)?;
+
         writeln!(writer, "Per-File Coverage Details:")?;
         for (idx, file_coverage) in project_coverage.files.iter().enumerate() {
             writeln!(writer, "--------------------------------------------------")?;
             writeln!(writer, "[{}/{}] File: {}", idx + 1, project_coverage.files.len(), file_coverage.file_path.display())?;
             writeln!(writer, "  Metrics:")?;
-            writeln!(writer, "    {}", Self::format_coverage_metrics(&file_coverage.functions, "Functions"))?;
-            writeln!(writer, "    {}", Self::format_coverage_metrics(&file_coverage.branches, "Branches"))?;
-            writeln!(writer, "    {}", Self::format_coverage_metrics(&file_coverage.exceptions, "Exceptions"))?;
-            writeln!(writer, "    {}", Self::format_coverage_metrics(&file_coverage.overall, "Overall File"))?;
-            
+            writeln!(writer, "    {}", self.colorize_percentage(file_coverage.functions.percentage, &Self::format_coverage_metrics(&file_coverage.functions, "Functions")))?;
+            writeln!(writer, "    {}", self.colorize_percentage(file_coverage.branches.percentage, &Self::format_coverage_metrics(&file_coverage.branches, "Branches")))?;
+            writeln!(writer, "    {}", self.colorize_percentage(file_coverage.exceptions.percentage, &Self::format_coverage_metrics(&file_coverage.exceptions, "Exceptions")))?;
+            writeln!(writer, "    {}", self.colorize_percentage(file_coverage.overall.percentage, &Self::format_coverage_metrics(&file_coverage.overall, "Overall File")))?;
+
             if !file_coverage.uncovered_functions.is_empty() {
                 writeln!(writer, "  Uncovered Functions ({}):", file_coverage.uncovered_functions.len())?;
                 for func_info in &file_coverage.uncovered_functions {
@@ -1,9 +1,7 @@
 use std::path::PathBuf; // Removed unused `Path`
 use crate::config::Config;
-use crate::utils::file_utils;
-use regex::Regex;
-use walkdir::WalkDir;
-use log::{debug, error, info, warn};
+use crate::utils::file_utils::{self, GlobPattern, ListOptions};
+use log::{debug, info, warn};
 
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -16,32 +14,20 @@ pub struct SourceFile {
 pub struct SourceManager<'a> {
     config: &'a Config,
     source_files: Vec<SourceFile>,
-    exclude_patterns: Vec<Regex>,
+    exclude_patterns: Vec<GlobPattern>,
 }
 
 #[allow(dead_code)]
 impl<'a> SourceManager<'a> {
     pub fn new(config: &'a Config) -> Result<Self, String> {
         debug!("Initializing SourceManager...");
-        let mut exclude_patterns = Vec::new();
         if config.scan.excludes.is_empty() {
             info!("No exclude patterns specified in configuration.");
         } else {
-            for pattern_str in &config.scan.excludes {
-                match Regex::new(pattern_str) {
-                    Ok(re) => {
-                        debug!("Successfully compiled exclude regex: {}", pattern_str);
-                        exclude_patterns.push(re);
-                    }
-                    Err(e) => {
-                        let err_msg = format!("Failed to compile exclude regex pattern '{}': {}", pattern_str, e);
-                        error!("{}", err_msg);
-                        return Err(err_msg);
-                    }
-                }
-            }
-            info!("Successfully compiled {} exclude patterns.", exclude_patterns.len());
+            debug!("Compiling {} exclude glob pattern(s).", config.scan.excludes.len());
         }
+        let exclude_patterns: Vec<GlobPattern> =
+            config.scan.excludes.iter().map(|pattern_str| GlobPattern::new(pattern_str)).collect();
 
         Ok(SourceManager {
             config,
@@ -61,9 +47,9 @@ impl<'a> SourceManager<'a> {
 
         for scan_dir_str in &self.config.scan.directories {
             let scan_dir_config_path = PathBuf::from(scan_dir_str); // Path as specified in config
-            
-            // Resolve the scan directory to an absolute path for consistent processing
-            let absolute_scan_dir = match file_utils::to_absolute_path(&scan_dir_config_path) {
+
+            // Resolve the scan directory to its real on-disk location for consistent processing.
+            let absolute_scan_dir = match file_utils::canonicalize_path(&scan_dir_config_path) {
                 Ok(abs_path) => {
                     if !abs_path.exists() || !abs_path.is_dir() {
                         warn!("Scan directory '{}' (resolved to '{}') does not exist or is not a directory. Skipping.", scan_dir_config_path.display(), abs_path.display());
@@ -73,30 +59,37 @@ impl<'a> SourceManager<'a> {
                     abs_path
                 },
                 Err(e) => {
-                    warn!("Could not get absolute path for scan directory '{}': {}. Skipping.", scan_dir_config_path.display(), e);
-                    continue; 
+                    warn!("Could not resolve scan directory '{}': {}. Skipping.", scan_dir_config_path.display(), e);
+                    continue;
                 }
             };
 
-
-            for entry_result in WalkDir::new(&absolute_scan_dir).into_iter() {
-                let entry = match entry_result {
-                    Ok(e) => e,
+            let list_options = ListOptions {
+                respect_gitignore: true,
+                exclude_globs: self.exclude_patterns.clone(),
+            };
+            let (candidates, bad_matches) =
+                match file_utils::list_files_parallel(&absolute_scan_dir, None, true, &list_options) {
+                    Ok(result) => result,
                     Err(e) => {
-                        warn!("Error accessing entry in directory '{}': {}. Skipping.", absolute_scan_dir.display(), e);
+                        warn!("Failed to list files under '{}': {}. Skipping.", absolute_scan_dir.display(), e);
                         continue;
                     }
                 };
 
-                if !entry.file_type().is_file() {
-                    continue;
-                }
+            for bad_match in &bad_matches {
+                warn!(
+                    "Skipping '{}' while scanning '{}': {:?}",
+                    bad_match.path.display(),
+                    absolute_scan_dir.display(),
+                    bad_match.reason
+                );
+            }
 
-                let absolute_entry_path = entry.path().to_path_buf();
-                
+            for absolute_entry_path in candidates {
                 let file_extension_os = absolute_entry_path.extension();
                 let file_extension_str = file_extension_os.and_then(|os| os.to_str());
-                
+
                 if let Some(ext_str) = file_extension_str {
                     let dot_ext = format!(".{}", ext_str); // Add dot to match config like ".cpp"
                     if !self.config.scan.file_types.iter().any(|ft| ft.eq_ignore_ascii_case(&dot_ext)) {
@@ -107,32 +100,19 @@ impl<'a> SourceManager<'a> {
                     // No extension or invalid UTF-8 extension
                     if !self.config.scan.file_types.is_empty() { // Only skip if file_types are specified
                         debug!("Skipping file '{}': no valid extension found.", absolute_entry_path.display());
-                        continue; 
+                        continue;
                     }
                 }
 
-                let path_str_for_regex = absolute_entry_path.to_string_lossy();
-                let mut excluded = false;
-                for re in &self.exclude_patterns {
-                    if re.is_match(&path_str_for_regex) {
-                        debug!("Skipping file '{}': matches exclude pattern '{}'", absolute_entry_path.display(), re.as_str());
-                        excluded = true;
-                        break;
-                    }
-                }
-                if excluded {
-                    continue;
-                }
-                
                 // Calculate relative path based on the resolved absolute_scan_dir
                 let relative_path = match absolute_entry_path.strip_prefix(&absolute_scan_dir) {
                     Ok(rel_path) => rel_path.to_path_buf(),
                     Err(_) => {
                         warn!("Could not strip prefix '{}' from '{}'. Using file name as relative path.", absolute_scan_dir.display(), absolute_entry_path.display());
-                        PathBuf::from(entry.file_name())
+                        PathBuf::from(absolute_entry_path.file_name().unwrap_or_default())
                     }
                 };
-                
+
                 debug!("Collected source file: '{}' (relative to '{}')", absolute_entry_path.display(), absolute_scan_dir.display());
                 self.source_files.push(SourceFile {
                     absolute_path: absolute_entry_path,
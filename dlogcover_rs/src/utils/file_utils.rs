@@ -1,18 +1,15 @@
 use std::fs::{self, File};
-use std::io::{self}; 
+use std::io::{self, Write as _};
+use std::ops::Deref;
 use std::path::{Path, PathBuf};
-use std::sync::Mutex; 
+use rayon::prelude::*;
 use regex::Regex;
 use walkdir::WalkDir;
-use rand::Rng; 
+use rand::Rng;
 
 use log::{debug, error, info, warn};
 
-lazy_static::lazy_static! {
-    static ref TEMP_FILES_TO_CLEANUP: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
-}
-
-#[allow(dead_code)] 
+#[allow(dead_code)]
 fn log_fs_error<P: AsRef<Path>>(operation: &str, path: P, e: &io::Error) {
     error!("{} at path '{}': {}", operation, path.as_ref().display(), e);
 }
@@ -86,24 +83,51 @@ pub fn read_file_to_string(path: impl AsRef<Path>) -> io::Result<String> {
 pub fn write_file(path: impl AsRef<Path>, content: &str) -> io::Result<()> {
     let path_ref = path.as_ref();
     debug!("Writing file: '{}', size: {} bytes", path_ref.display(), content.len());
+    write_file_atomic(path_ref, content)
+}
 
-    if let Some(parent_dir) = path_ref.parent() {
-        if !parent_dir.as_os_str().is_empty() && !(parent_dir.exists() && parent_dir.is_dir()) { 
-            info!("Parent directory '{}' does not exist. Creating.", parent_dir.display());
-            create_directory(parent_dir)?;
-        }
+/// Writes `content` to `path` atomically: the content is first written to a sibling temp file in
+/// `path`'s own directory (so the final rename stays on one filesystem), `fsync`'d, then moved
+/// into place with a single `fs::rename`. A reader can never observe a partially-written file at
+/// `path` — only the previous content or the complete new content. The temp file is removed on
+/// any failure so no stray `.tmp` is left behind.
+#[allow(dead_code)]
+pub fn write_file_atomic(path: impl AsRef<Path>, content: &str) -> io::Result<()> {
+    let path_ref = path.as_ref();
+    debug!("Atomically writing file: '{}', size: {} bytes", path_ref.display(), content.len());
+
+    let parent_dir = match path_ref.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    if !(parent_dir.exists() && parent_dir.is_dir()) {
+        info!("Parent directory '{}' does not exist. Creating.", parent_dir.display());
+        create_directory(parent_dir)?;
     }
 
-    match fs::write(path_ref, content) {
-        Ok(()) => {
-            debug!("Successfully written file: '{}'", path_ref.display());
-            Ok(())
-        }
-        Err(e) => {
-            error!("Failed to write file at path '{}': {}", path_ref.display(), e);
-            Err(e)
-        }
+    let temp_file_name = generate_random_filename(".dlogcover_atomic_", ".tmp");
+    let temp_path = parent_dir.join(temp_file_name);
+
+    let write_result = (|| -> io::Result<()> {
+        let mut temp_file = File::create(&temp_path)?;
+        temp_file.write_all(content.as_bytes())?;
+        temp_file.sync_all()
+    })();
+
+    if let Err(e) = write_result {
+        error!("Failed to write temp file '{}' for atomic write of '{}': {}", temp_path.display(), path_ref.display(), e);
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+
+    if let Err(e) = fs::rename(&temp_path, path_ref) {
+        error!("Failed to rename temp file '{}' to '{}': {}", temp_path.display(), path_ref.display(), e);
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
     }
+
+    debug!("Successfully atomically written file: '{}'", path_ref.display());
+    Ok(())
 }
 
 #[allow(dead_code)]
@@ -194,23 +218,51 @@ pub fn get_directory_name(path: impl AsRef<Path>) -> Option<String> {
     }
 }
 
+/// Extra knobs for [`list_files_with_options`] beyond the plain directory/pattern/recursive
+/// parameters that [`list_files`] takes. Kept as its own struct (rather than growing
+/// `list_files`'s argument list further) so future options can be added without another
+/// signature change at every call site.
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    /// Skip paths excluded by any `.gitignore`/`.ignore` file encountered while descending
+    /// into `dir_path`, the same way `git status`/`git add` would.
+    pub respect_gitignore: bool,
+    /// Additional glob patterns (see [`GlobPattern`]) to exclude, evaluated with
+    /// last-match-wins precedence alongside `respect_gitignore`'s file-based rules.
+    pub exclude_globs: Vec<GlobPattern>,
+}
+
 #[allow(dead_code)]
 pub fn list_files(
     dir_path: impl AsRef<Path>,
-    pattern: Option<&str>, 
+    pattern: Option<&str>,
+    recursive: bool,
+) -> io::Result<Vec<PathBuf>> {
+    list_files_with_options(dir_path, pattern, recursive, &ListOptions::default())
+}
+
+/// Like [`list_files`], but honors `options`: `respect_gitignore` makes the walk skip anything
+/// `.gitignore`/`.ignore` files along the way would exclude, instead of descending into e.g.
+/// `target/` or `.git/` unconditionally, and `exclude_globs` drops files matching those globs.
+#[allow(dead_code)]
+pub fn list_files_with_options(
+    dir_path: impl AsRef<Path>,
+    pattern: Option<&str>,
     recursive: bool,
+    options: &ListOptions,
 ) -> io::Result<Vec<PathBuf>> {
     let dir_path_ref = dir_path.as_ref();
     debug!(
-        "Listing files: Directory: '{}', Pattern: {:?}, Recursive: {}",
+        "Listing files: Directory: '{}', Pattern: {:?}, Recursive: {}, Respect gitignore: {}",
         dir_path_ref.display(),
         pattern.unwrap_or("<无>"),
-        recursive
+        recursive,
+        options.respect_gitignore,
     );
 
-    if !(dir_path_ref.exists() && dir_path_ref.is_dir()) { 
+    if !(dir_path_ref.exists() && dir_path_ref.is_dir()) {
         warn!("Directory does not exist or is not a directory: '{}'", dir_path_ref.display());
-        return Ok(Vec::new()); 
+        return Ok(Vec::new());
     }
 
     let pattern_regex = match pattern {
@@ -223,24 +275,74 @@ pub fn list_files(
                 }
             }
         },
-        _ => None, 
+        _ => None,
     };
 
     let mut files = Vec::new();
-    let walker = WalkDir::new(dir_path_ref).max_depth(if recursive { usize::MAX } else { 1 });
+    // Each entry is the directory an ignore file was found in, plus the rules it contributed.
+    // Pushed on entering a directory, popped once the walk backtracks past it, so at any point
+    // it holds exactly the ancestor chain's rules from shallowest to deepest.
+    let mut ignore_stack: Vec<(PathBuf, Vec<IgnoreRule>)> = Vec::new();
+    let mut walker = WalkDir::new(dir_path_ref)
+        .max_depth(if recursive { usize::MAX } else { 1 })
+        .into_iter();
 
-    for entry_result in walker.into_iter() {
+    while let Some(entry_result) = walker.next() {
         match entry_result {
             Ok(entry) => {
-                if entry.file_type().is_file() {
-                    let file_path = entry.path();
-                    if let Some(re) = &pattern_regex {
-                        if file_path.to_str().map_or(false, |s| re.is_match(s)) {
-                            files.push(file_path.to_path_buf());
+                let entry_path = entry.path();
+
+                if options.respect_gitignore {
+                    while let Some((stack_dir, _)) = ignore_stack.last() {
+                        if entry_path.starts_with(stack_dir) {
+                            break;
+                        }
+                        ignore_stack.pop();
+                    }
+                }
+
+                if entry.file_type().is_dir() {
+                    if options.respect_gitignore {
+                        let mut rules = Vec::new();
+                        for ignore_file_name in [".gitignore", ".ignore"] {
+                            let ignore_file_path = entry_path.join(ignore_file_name);
+                            if ignore_file_path.is_file() {
+                                rules.extend(parse_ignore_file(&ignore_file_path));
+                            }
+                        }
+                        let is_ignored = entry_path != dir_path_ref
+                            && is_path_ignored(&ignore_stack, entry_path, true);
+                        ignore_stack.push((entry_path.to_path_buf(), rules));
+                        if is_ignored {
+                            debug!("Skipping ignored directory: '{}'", entry_path.display());
+                            ignore_stack.pop();
+                            walker.skip_current_dir();
                         }
-                    } else { 
-                        files.push(file_path.to_path_buf());
                     }
+                    continue;
+                }
+
+                if !entry.file_type().is_file() {
+                    continue;
+                }
+
+                if options.respect_gitignore && is_path_ignored(&ignore_stack, entry_path, false) {
+                    continue;
+                }
+
+                if !options.exclude_globs.is_empty()
+                    && is_excluded_by_globs(&options.exclude_globs, &entry_path.to_string_lossy())
+                {
+                    debug!("Skipping file '{}': matches an exclude glob.", entry_path.display());
+                    continue;
+                }
+
+                if let Some(re) = &pattern_regex {
+                    if entry_path.to_str().map_or(false, |s| re.is_match(s)) {
+                        files.push(entry_path.to_path_buf());
+                    }
+                } else {
+                    files.push(entry_path.to_path_buf());
                 }
             }
             Err(e) => {
@@ -252,12 +354,371 @@ pub fn list_files(
     Ok(files)
 }
 
+/// Why a path couldn't be included as a match by [`list_files_parallel`].
+#[derive(Debug, Clone)]
+pub enum BadMatchReason {
+    /// The OS reported this raw `errno` while accessing the path (permission denied, a stale
+    /// handle, etc). `0` if walkdir didn't have an underlying `io::Error` to read one from.
+    OsError(i32),
+    /// The path exists and was otherwise a candidate match, but isn't a regular file.
+    BadType(BadFileType),
+}
+
+/// The non-regular-file kind behind a [`BadMatchReason::BadType`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BadFileType {
+    Directory,
+    Symlink,
+    /// A device, FIFO, or Unix domain socket — anything that is neither a regular file, a
+    /// directory, nor a symlink.
+    Special,
+}
+
+/// One path [`list_files_parallel`] could not count as a match, and why.
+#[derive(Debug, Clone)]
+pub struct BadMatch {
+    pub path: PathBuf,
+    pub reason: BadMatchReason,
+}
+
+/// Like [`list_files_with_options`], but classifies each walked entry (regex/glob matching,
+/// telling apart a plain access error from a wrong-file-type match) across a rayon thread
+/// pool, and reports every path it had to skip — alongside why — instead of only
+/// `warn!`-logging access errors and silently dropping everything else. The directory descent
+/// itself stays single-threaded: `ListOptions::respect_gitignore`'s per-directory rule stack
+/// depends on ancestors having been visited first, so only the independent per-entry
+/// classification work is parallelized. Both returned lists are sorted by path so output is
+/// deterministic despite the parallel collection.
+#[allow(dead_code)]
+pub fn list_files_parallel(
+    dir_path: impl AsRef<Path>,
+    pattern: Option<&str>,
+    recursive: bool,
+    options: &ListOptions,
+) -> io::Result<(Vec<PathBuf>, Vec<BadMatch>)> {
+    let dir_path_ref = dir_path.as_ref();
+    debug!(
+        "Listing files in parallel: Directory: '{}', Pattern: {:?}, Recursive: {}",
+        dir_path_ref.display(),
+        pattern.unwrap_or("<无>"),
+        recursive
+    );
+
+    if !(dir_path_ref.exists() && dir_path_ref.is_dir()) {
+        warn!("Directory does not exist or is not a directory: '{}'", dir_path_ref.display());
+        return Ok((Vec::new(), Vec::new()));
+    }
+
+    let pattern_regex = match pattern {
+        Some(p_str) if !p_str.is_empty() => Some(Regex::new(p_str).map_err(|e| {
+            error!("Invalid regex pattern '{}': {}", p_str, e);
+            io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid regex pattern: {}", e))
+        })?),
+        _ => None,
+    };
+
+    enum RawEntry {
+        Candidate(PathBuf, fs::FileType),
+        AccessError(PathBuf, Option<i32>),
+    }
+
+    let mut raw_entries = Vec::new();
+    let mut ignore_stack: Vec<(PathBuf, Vec<IgnoreRule>)> = Vec::new();
+    let mut walker = WalkDir::new(dir_path_ref)
+        .max_depth(if recursive { usize::MAX } else { 1 })
+        .into_iter();
+
+    while let Some(entry_result) = walker.next() {
+        match entry_result {
+            Ok(entry) => {
+                let entry_path = entry.path();
+
+                if options.respect_gitignore {
+                    while let Some((stack_dir, _)) = ignore_stack.last() {
+                        if entry_path.starts_with(stack_dir) {
+                            break;
+                        }
+                        ignore_stack.pop();
+                    }
+                }
+
+                if entry.file_type().is_dir() {
+                    if options.respect_gitignore {
+                        let mut rules = Vec::new();
+                        for ignore_file_name in [".gitignore", ".ignore"] {
+                            let ignore_file_path = entry_path.join(ignore_file_name);
+                            if ignore_file_path.is_file() {
+                                rules.extend(parse_ignore_file(&ignore_file_path));
+                            }
+                        }
+                        let is_ignored = entry_path != dir_path_ref
+                            && is_path_ignored(&ignore_stack, entry_path, true);
+                        ignore_stack.push((entry_path.to_path_buf(), rules));
+                        if is_ignored {
+                            debug!("Skipping ignored directory: '{}'", entry_path.display());
+                            ignore_stack.pop();
+                            walker.skip_current_dir();
+                            continue;
+                        }
+                    }
+                    // A directory is only worth reporting as a bad match when it would
+                    // otherwise have satisfied the caller's own selection pattern — which, with
+                    // no pattern at all, every path trivially does (matching how `pattern_regex`
+                    // is treated for files below).
+                    let would_match = pattern_regex
+                        .as_ref()
+                        .map_or(true, |re| re.is_match(&entry_path.to_string_lossy()));
+                    if would_match {
+                        raw_entries.push(RawEntry::Candidate(entry_path.to_path_buf(), entry.file_type()));
+                    }
+                    continue;
+                }
+
+                if options.respect_gitignore && is_path_ignored(&ignore_stack, entry_path, false) {
+                    continue;
+                }
+
+                raw_entries.push(RawEntry::Candidate(entry_path.to_path_buf(), entry.file_type()));
+            }
+            Err(e) => {
+                let path = e.path().map(Path::to_path_buf).unwrap_or_default();
+                let errno = e.io_error().and_then(|io_err| io_err.raw_os_error());
+                warn!("Error accessing entry in directory '{}': {}", dir_path_ref.display(), e);
+                raw_entries.push(RawEntry::AccessError(path, errno));
+            }
+        }
+    }
+
+    let classified: Vec<Result<Option<PathBuf>, BadMatch>> = raw_entries
+        .par_iter()
+        .map(|raw| match raw {
+            RawEntry::AccessError(path, errno) => {
+                Err(BadMatch { path: path.clone(), reason: BadMatchReason::OsError(errno.unwrap_or(0)) })
+            }
+            RawEntry::Candidate(path, file_type) => {
+                if !file_type.is_file() {
+                    let bad_type = if file_type.is_dir() {
+                        BadFileType::Directory
+                    } else if file_type.is_symlink() {
+                        BadFileType::Symlink
+                    } else {
+                        BadFileType::Special
+                    };
+                    return Err(BadMatch { path: path.clone(), reason: BadMatchReason::BadType(bad_type) });
+                }
+
+                if !options.exclude_globs.is_empty()
+                    && is_excluded_by_globs(&options.exclude_globs, &path.to_string_lossy())
+                {
+                    return Ok(None);
+                }
+
+                let matched = match &pattern_regex {
+                    Some(re) => path.to_str().map_or(false, |s| re.is_match(s)),
+                    None => true,
+                };
+                Ok(matched.then(|| path.clone()))
+            }
+        })
+        .collect();
+
+    let mut files = Vec::new();
+    let mut bad_matches = Vec::new();
+    for item in classified {
+        match item {
+            Ok(Some(path)) => files.push(path),
+            Ok(None) => {}
+            Err(bad_match) => bad_matches.push(bad_match),
+        }
+    }
+    files.sort();
+    bad_matches.sort_by(|a, b| a.path.cmp(&b.path));
+
+    debug!(
+        "Found {} files ({} bad matches) in '{}'",
+        files.len(),
+        bad_matches.len(),
+        dir_path_ref.display()
+    );
+    Ok((files, bad_matches))
+}
+
+/// One parsed line of a `.gitignore`/`.ignore` file.
+#[derive(Debug, Clone)]
+struct IgnoreRule {
+    /// The glob itself, with any leading `!` and trailing `/` already stripped off.
+    glob: String,
+    /// `true` for a line starting with `!`: a later match re-includes a path an earlier rule
+    /// excluded, rather than excluding it.
+    negate: bool,
+    /// `true` for a line ending in `/`: only matches directories, not files of the same name.
+    dir_only: bool,
+    /// `true` when the glob contains a `/` other than a single trailing one, meaning it is
+    /// anchored to the directory the ignore file lives in rather than matching at any depth.
+    anchored: bool,
+}
+
+fn parse_ignore_file(path: &Path) -> Vec<IgnoreRule> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            warn!("Could not read ignore file '{}': {}", path.display(), e);
+            return Vec::new();
+        }
+    };
+
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_end();
+            if line.is_empty() || line.trim_start().starts_with('#') {
+                return None;
+            }
+            let negate = line.starts_with('!');
+            let mut glob = if negate { &line[1..] } else { line };
+            let dir_only = glob.ends_with('/');
+            if dir_only {
+                glob = &glob[..glob.len() - 1];
+            }
+            let anchored = glob.contains('/');
+            let glob = glob.trim_start_matches('/').to_string();
+            if glob.is_empty() {
+                return None;
+            }
+            Some(IgnoreRule { glob, negate, dir_only, anchored })
+        })
+        .collect()
+}
+
+/// Whether `candidate` is excluded by the accumulated ignore rules in `stack`. Rules are
+/// applied shallowest ancestor first so that a deeper directory's own `.gitignore` has the
+/// final say, and within a single file later lines win too, matching git's own precedence:
+/// the last matching rule (of either polarity) decides the outcome.
+fn is_path_ignored(stack: &[(PathBuf, Vec<IgnoreRule>)], candidate: &Path, is_dir: bool) -> bool {
+    let mut ignored = false;
+    for (rule_dir, rules) in stack {
+        let relative = match candidate.strip_prefix(rule_dir) {
+            Ok(relative) if !relative.as_os_str().is_empty() => relative,
+            _ => continue,
+        };
+        let relative_str = relative.to_string_lossy().replace('\\', "/");
+        for rule in rules {
+            if ignore_rule_matches(rule, &relative_str, is_dir) {
+                ignored = !rule.negate;
+            }
+        }
+    }
+    ignored
+}
+
+fn ignore_rule_matches(rule: &IgnoreRule, relative_path: &str, is_dir: bool) -> bool {
+    if rule.dir_only && !is_dir {
+        return false;
+    }
+    if rule.anchored {
+        glob_match(&rule.glob, relative_path)
+    } else {
+        relative_path
+            .rsplit('/')
+            .next()
+            .map_or(false, |basename| glob_match(&rule.glob, basename))
+            || glob_match(&rule.glob, relative_path)
+    }
+}
+
+/// A small gitignore-style glob matcher: `?` matches one non-`/` character, `*` matches a run
+/// of non-`/` characters, and `**` matches any run of characters including `/` (i.e. it can
+/// span directory boundaries), mirroring the subset of glob syntax `.gitignore` files use.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    glob_match_bytes(pattern.as_bytes(), text.as_bytes())
+}
+
+fn glob_match_bytes(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some(b'*') if pattern.get(1) == Some(&b'*') => {
+            let mut rest = &pattern[2..];
+            if rest.first() != Some(&b'/') {
+                // A `**` not followed by `/` (e.g. a trailing one) absorbs any run, `/` included.
+                return (0..=text.len()).any(|i| glob_match_bytes(rest, &text[i..]));
+            }
+            rest = &rest[1..];
+            if glob_match_bytes(rest, text) {
+                return true;
+            }
+            (0..text.len()).any(|i| text[i] == b'/' && glob_match_bytes(rest, &text[i + 1..]))
+        }
+        Some(b'*') => {
+            let rest = &pattern[1..];
+            if glob_match_bytes(rest, text) {
+                return true;
+            }
+            for i in 0..text.len() {
+                if text[i] == b'/' {
+                    break;
+                }
+                if glob_match_bytes(rest, &text[i + 1..]) {
+                    return true;
+                }
+            }
+            false
+        }
+        Some(b'?') => matches!(text.first(), Some(&c) if c != b'/') && glob_match_bytes(&pattern[1..], &text[1..]),
+        Some(&c) => matches!(text.first(), Some(&tc) if tc == c) && glob_match_bytes(&pattern[1..], &text[1..]),
+    }
+}
+
+/// A single gitignore-style glob, as passed via `--exclude`/`scan.excludes` or
+/// [`ListOptions::exclude_globs`]. Unlike the raw `regex` crate patterns these replace, `*.tmp`
+/// and `**/test/**` work directly instead of needing regex-escaping. A leading `!` flips the
+/// pattern into a re-include; see [`is_excluded_by_globs`] for how a list of these is resolved.
+#[derive(Debug, Clone)]
+pub struct GlobPattern {
+    glob: String,
+    negate: bool,
+    anchored: bool,
+}
+
+impl GlobPattern {
+    pub fn new(pattern: &str) -> Self {
+        let negate = pattern.starts_with('!');
+        let glob = if negate { &pattern[1..] } else { pattern };
+        let anchored = glob.contains('/');
+        GlobPattern { glob: glob.to_string(), negate, anchored }
+    }
+
+    fn matches_path(&self, path: &str) -> bool {
+        if self.anchored {
+            glob_match(&self.glob, path)
+        } else {
+            path.rsplit('/').next().map_or(false, |basename| glob_match(&self.glob, basename))
+        }
+    }
+}
+
+/// Evaluates `patterns` against `path` (a filesystem path, `\` normalized to `/`) with
+/// gitignore's last-match-wins precedence: the final pattern in the list that matches decides
+/// the outcome, so a broad exclude followed by a narrower `!`-prefixed re-include works as
+/// expected. Returns `false` if no pattern matches.
+#[allow(dead_code)]
+pub fn is_excluded_by_globs(patterns: &[GlobPattern], path: &str) -> bool {
+    let path = path.replace('\\', "/");
+    let mut excluded = false;
+    for pattern in patterns {
+        if pattern.matches_path(&path) {
+            excluded = !pattern.negate;
+        }
+    }
+    excluded
+}
+
 #[allow(dead_code)]
 pub fn list_files_with_filter(
     dir_path: impl AsRef<Path>,
-    filter: &dyn Fn(&Path) -> bool, 
+    filter: &dyn Fn(&Path) -> bool,
     pattern: Option<&str>,
     recursive: bool,
+    options: &ListOptions,
 ) -> io::Result<Vec<PathBuf>> {
     let dir_path_ref = dir_path.as_ref();
      debug!(
@@ -266,10 +727,10 @@ pub fn list_files_with_filter(
         pattern.unwrap_or("<无>"),
         recursive
     );
-    
-    let base_files = list_files(dir_path_ref, pattern, recursive)?;
+
+    let base_files = list_files_with_options(dir_path_ref, pattern, recursive, options)?;
     let filtered_files: Vec<PathBuf> = base_files.into_iter().filter(|path_buf| filter(path_buf.as_path())).collect();
-    
+
     debug!("Found {} files matching filter in '{}'", filtered_files.len(), dir_path_ref.display());
     Ok(filtered_files)
 }
@@ -318,7 +779,65 @@ fn generate_random_filename(base_prefix: &str, suffix: &str) -> String {
 }
 
 #[allow(dead_code)]
-pub fn create_temp_file_with_content(content: &str) -> io::Result<PathBuf> {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TempFileType {
+    Empty,
+    WithContent,
+}
+
+/// An owned handle to a temp file created by [`create_temp_file`] or
+/// [`create_temp_file_with_content`]. The file is deleted when the handle is dropped —
+/// including on an early return or panic — rather than relying on a separately-called cleanup
+/// function and a global registry, which leaks files on any path that forgets to call it or
+/// panics first. Call [`TempFile::keep`] to defuse deletion when the path needs to outlive this
+/// handle.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct TempFile {
+    path: PathBuf,
+    keep: bool,
+}
+
+impl TempFile {
+    /// Defuses automatic deletion and returns the owned path for the caller to manage from
+    /// here on; the file is left on disk once this handle is dropped.
+    #[allow(dead_code)]
+    pub fn keep(mut self) -> PathBuf {
+        self.keep = true;
+        std::mem::take(&mut self.path)
+    }
+}
+
+impl Deref for TempFile {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl AsRef<Path> for TempFile {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if self.keep || self.path.as_os_str().is_empty() {
+            return;
+        }
+        match fs::remove_file(&self.path) {
+            Ok(()) => debug!("Deleted temp file on drop: '{}'", self.path.display()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Failed to delete temp file '{}' on drop: {}", self.path.display(), e),
+        }
+    }
+}
+
+/// Writes `content` to a newly created temp file and returns a [`TempFile`] guard owning it.
+#[allow(dead_code)]
+pub fn create_temp_file_with_content(content: &str) -> io::Result<TempFile> {
     debug!("Creating temp file with content, size: {} bytes", content.len());
     let temp_dir = std::env::temp_dir();
     let file_name = generate_random_filename("dlogcover_temp_", ".tmp");
@@ -326,24 +845,14 @@ pub fn create_temp_file_with_content(content: &str) -> io::Result<PathBuf> {
 
     write_file(&temp_file_path, content)?;
 
-    if let Ok(mut guard) = TEMP_FILES_TO_CLEANUP.lock() {
-        guard.push(temp_file_path.clone());
-        info!("Created temp file '{}' with content, scheduled for cleanup.", temp_file_path.display());
-    } else {
-        error!("Failed to lock TEMP_FILES_TO_CLEANUP to add: {}", temp_file_path.display());
-    }
-    Ok(temp_file_path)
-}
-
-#[allow(dead_code)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum TempFileType {
-    Empty,
-    WithContent, 
+    info!("Created temp file '{}' with content; will be deleted when dropped.", temp_file_path.display());
+    Ok(TempFile { path: temp_file_path, keep: false })
 }
 
+/// Creates a temp file (empty, or with placeholder content per `file_type`) and returns a
+/// [`TempFile`] guard owning it.
 #[allow(dead_code)]
-pub fn create_temp_file(prefix: &str, file_type: TempFileType) -> io::Result<PathBuf> {
+pub fn create_temp_file(prefix: &str, file_type: TempFileType) -> io::Result<TempFile> {
     debug!("Creating temp file, prefix: '{}', type: {:?}", prefix, file_type);
     let temp_dir = std::env::temp_dir();
     let file_name_prefix = format!("{}_", prefix);
@@ -352,7 +861,7 @@ pub fn create_temp_file(prefix: &str, file_type: TempFileType) -> io::Result<Pat
 
     match file_type {
         TempFileType::Empty => {
-            File::create(&temp_file_path)?; 
+            File::create(&temp_file_path)?;
             debug!("Created empty temp file: '{}'", temp_file_path.display());
         }
         TempFileType::WithContent => {
@@ -361,42 +870,8 @@ pub fn create_temp_file(prefix: &str, file_type: TempFileType) -> io::Result<Pat
         }
     }
 
-    if let Ok(mut guard) = TEMP_FILES_TO_CLEANUP.lock() {
-        guard.push(temp_file_path.clone());
-        info!("Created temp file '{}' ({:?}), scheduled for cleanup.", temp_file_path.display(), file_type);
-    } else {
-        error!("Failed to lock TEMP_FILES_TO_CLEANUP to add: {}", temp_file_path.display());
-    }
-    Ok(temp_file_path)
-}
-
-#[allow(dead_code)]
-pub fn cleanup_temp_files() {
-    if let Ok(mut guard) = TEMP_FILES_TO_CLEANUP.lock() {
-        if guard.is_empty() {
-            debug!("No temp files registered for cleanup.");
-            return;
-        }
-        info!("Starting cleanup of {} registered temp files.", guard.len());
-        let mut remaining_files = Vec::new(); 
-        for file_path in guard.drain(..) { 
-            match fs::remove_file(&file_path) {
-                Ok(_) => debug!("Successfully deleted temp file: '{}'", file_path.display()),
-                Err(e) => {
-                    warn!("Failed to delete temp file '{}': {}", file_path.display(), e);
-                    remaining_files.push(file_path); 
-                }
-            }
-        }
-        *guard = remaining_files; 
-        if guard.is_empty() {
-            info!("All registered temp files cleaned up successfully.");
-        } else {
-            warn!("{} temp files could not be cleaned up and remain in tracking list.", guard.len());
-        }
-    } else {
-        error!("Failed to acquire lock for temp file cleanup. Cleanup skipped.");
-    }
+    info!("Created temp file '{}' ({:?}); will be deleted when dropped.", temp_file_path.display(), file_type);
+    Ok(TempFile { path: temp_file_path, keep: false })
 }
 
 #[allow(dead_code)]
@@ -460,17 +935,34 @@ pub fn normalize_path(path: impl AsRef<Path>) -> PathBuf {
 
 #[allow(dead_code)]
 pub fn get_relative_path(path: impl AsRef<Path>, base: impl AsRef<Path>) -> io::Result<PathBuf> {
-    let original_path_display = path.as_ref().display().to_string(); 
-    let original_base_display = base.as_ref().display().to_string(); 
+    get_relative_path_with_options(path, base, false)
+}
+
+/// Like [`get_relative_path`], but when `canonicalize` is `true`, resolves both `path` and
+/// `base` through [`canonicalize_path`] (following symlinks) instead of just
+/// [`to_absolute_path`]'s lexical cleanup first. Needed when either operand may be reached
+/// through a symlinked directory: computing the relative path lexically in that case can walk
+/// `..` back out through the symlink's real parent rather than its apparent one.
+#[allow(dead_code)]
+pub fn get_relative_path_with_options(
+    path: impl AsRef<Path>,
+    base: impl AsRef<Path>,
+    canonicalize: bool,
+) -> io::Result<PathBuf> {
+    let original_path_display = path.as_ref().display().to_string();
+    let original_base_display = base.as_ref().display().to_string();
 
-    let abs_path = match to_absolute_path(path.as_ref()) { 
+    let resolve: fn(&Path) -> io::Result<PathBuf> =
+        if canonicalize { canonicalize_path } else { to_absolute_path };
+
+    let abs_path = match resolve(path.as_ref()) {
         Ok(p) => p,
         Err(e) => {
             error!("get_relative_path (making path absolute) for '{}': {}", &original_path_display, e);
             return Err(e);
         }
     };
-    let abs_base = match to_absolute_path(base.as_ref()) { 
+    let abs_base = match resolve(base.as_ref()) {
         Ok(p) => p,
         Err(e) => {
             error!("get_relative_path (making base absolute) for '{}': {}", &original_base_display, e);
@@ -539,3 +1031,43 @@ pub fn to_absolute_path(path: impl AsRef<Path>) -> io::Result<PathBuf> {
     };
     Ok(normalize_path(result_path))
 }
+
+/// Resolves `path` to its real on-disk location via `fs::canonicalize`: symlinks are followed
+/// and the result is absolute. Unlike [`to_absolute_path`]/[`normalize_path`], which only clean
+/// up `.`/`..` lexically without touching the filesystem, this requires `path` to actually
+/// exist; if it doesn't (or any other I/O error occurs), falls back to `to_absolute_path`'s
+/// lexical result rather than failing outright. On Windows, `fs::canonicalize` prefixes its
+/// result with the verbatim `\\?\` marker, which is stripped here since the rest of this
+/// codebase's path handling doesn't expect it.
+#[allow(dead_code)]
+pub fn canonicalize_path(path: impl AsRef<Path>) -> io::Result<PathBuf> {
+    let path_ref = path.as_ref();
+    match fs::canonicalize(path_ref) {
+        Ok(canonical) => {
+            let stripped = strip_windows_verbatim_prefix(canonical);
+            debug!("Canonicalized path: '{}' -> '{}'", path_ref.display(), stripped.display());
+            Ok(stripped)
+        }
+        Err(e) => {
+            debug!(
+                "Could not canonicalize '{}' ({}); falling back to lexical normalization.",
+                path_ref.display(),
+                e
+            );
+            to_absolute_path(path_ref)
+        }
+    }
+}
+
+#[cfg(windows)]
+fn strip_windows_verbatim_prefix(path: PathBuf) -> PathBuf {
+    match path.to_str().and_then(|s| s.strip_prefix(r"\\?\")) {
+        Some(stripped) => PathBuf::from(stripped),
+        None => path,
+    }
+}
+
+#[cfg(not(windows))]
+fn strip_windows_verbatim_prefix(path: PathBuf) -> PathBuf {
+    path
+}
@@ -1,24 +1,345 @@
-use log::LevelFilter; // Removed Level
-use env_logger::{Builder, Env};
-use std::sync::Once;
+use log::LevelFilter;
+use env_logger::fmt::Formatter;
+use env_logger::{Builder, Target};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Mutex, Once};
+
+use crate::config::{IfExists, LogFormat, LoggingConfig};
 
 #[allow(dead_code)]
 static INIT: Once = Once::new();
 
+/// A caller-supplied line renderer, as accepted by [`init_logger_with_formatter`]. Mirrors
+/// `env_logger::Builder::format`'s own callback signature so it can be passed straight through.
+pub type CustomLogFormatter =
+    Box<dyn Fn(&mut Formatter, &log::Record) -> std::io::Result<()> + Send + Sync>;
+
+/// Computes the effective log level from repeatable `-v`/`-q` counts, starting at `Info`: each
+/// net step up (`verbose_count - quiet_count`) raises the level (`+1` -> `Debug`, `+2` or more ->
+/// `Trace`), each net step down lowers it (`-1` -> `Warn`, `-2` or less -> `Error`).
+#[allow(dead_code)]
+pub fn level_filter_from_verbosity(verbose_count: u8, quiet_count: u8) -> LevelFilter {
+    match verbose_count as i16 - quiet_count as i16 {
+        net if net >= 2 => LevelFilter::Trace,
+        1 => LevelFilter::Debug,
+        0 => LevelFilter::Info,
+        -1 => LevelFilter::Warn,
+        _ => LevelFilter::Error,
+    }
+}
+
+/// Initializes the global logger from `config`; `level_override`, when given, takes precedence
+/// over the config's level.
+/// `level_override` reflects `--log-level` if present, else the level computed from `-v`/`-q`
+/// counts, else `None` to defer to whatever level the config's `logging` section specifies.
+///
+/// `Journald` and `Syslog` modes fall back to stderr at the configured level (rather than
+/// silently discarding output, and without failing startup) when the corresponding socket isn't
+/// reachable — e.g. `journald` off Linux, or either daemon not running in a minimal container.
 #[allow(dead_code)]
-pub fn init_logger() {
+pub fn init_logger(config: &LoggingConfig, level_override: Option<LevelFilter>) -> Result<(), String> {
+    init_logger_with_formatter(config, level_override, None)
+}
+
+/// Same as [`init_logger`], but `custom_formatter` (when given) overrides `logging.format` for
+/// `StderrTerminal`/`File` logging — e.g. a caller that wants colorized, structured, or otherwise
+/// bespoke line rendering beyond the built-in `default`/`compact` choices. Has no effect for
+/// `Journald`/`Syslog`, which render through their own backend instead of `env_logger`.
+#[allow(dead_code)]
+pub fn init_logger_with_formatter(
+    config: &LoggingConfig,
+    level_override: Option<LevelFilter>,
+    custom_formatter: Option<CustomLogFormatter>,
+) -> Result<(), String> {
+    let mut result = Ok(());
+    let mut custom_formatter = custom_formatter;
     INIT.call_once(|| {
-        Builder::from_env(Env::default().default_filter_or("info"))
-            .try_init()
-            .expect("Failed to initialize logger");
-        log::info!("Logger initialized via env_logger.");
+        result = init_logger_once(config, level_override, custom_formatter.take());
     });
+    result
+}
+
+fn init_logger_once(
+    config: &LoggingConfig,
+    level_override: Option<LevelFilter>,
+    custom_formatter: Option<CustomLogFormatter>,
+) -> Result<(), String> {
+    let configured_level = match config {
+        LoggingConfig::StderrTerminal { level, .. } => level,
+        LoggingConfig::File { level, .. } => level,
+        LoggingConfig::Journald { level } => level,
+        LoggingConfig::Syslog { level } => level,
+    };
+    let level = match level_override {
+        Some(level) => level,
+        None => LevelFilter::from_str(configured_level)
+            .map_err(|e| format!("Invalid log level '{}': {}", configured_level, e))?,
+    };
+
+    match config {
+        LoggingConfig::StderrTerminal { format, filters, .. } => {
+            init_stderr_logger(level, *format, filters.as_deref(), custom_formatter)
+        }
+        LoggingConfig::File { path, if_exists, max_size_bytes, max_backups, format, filters, .. } => {
+            init_file_logger(path, *if_exists, *max_size_bytes, *max_backups, level, *format, filters.as_deref(), custom_formatter)
+        }
+        LoggingConfig::Journald { .. } => match try_init_journald(level) {
+            Ok(()) => {
+                log::info!("Logger initialized (mode: journald, level: {}).", level);
+                Ok(())
+            }
+            Err(e) => {
+                eprintln!("logging.mode = \"journald\" unavailable ({}); falling back to stderr.", e);
+                init_stderr_logger(level, LogFormat::Default, None, None)
+            }
+        },
+        LoggingConfig::Syslog { .. } => match try_init_syslog(level) {
+            Ok(()) => {
+                log::info!("Logger initialized (mode: syslog, level: {}).", level);
+                Ok(())
+            }
+            Err(e) => {
+                eprintln!("logging.mode = \"syslog\" unavailable ({}); falling back to stderr.", e);
+                init_stderr_logger(level, LogFormat::Default, None, None)
+            }
+        },
+    }
+}
+
+/// Builds the directive string passed to `Builder::parse_filters`: `base_level` first (so it
+/// becomes the default for any module with no more specific match), followed by `extra_filters`
+/// verbatim. `env_logger`'s directive matching picks the most specific module path for a given
+/// record regardless of ordering, so `extra_filters` entries transparently take precedence over
+/// `base_level` for the modules they name.
+fn build_filter_directives(base_level: LevelFilter, extra_filters: Option<&str>) -> String {
+    match extra_filters {
+        Some(extra) => format!("{},{}", base_level, extra),
+        None => base_level.to_string(),
+    }
+}
+
+fn apply_format(builder: &mut Builder, format: LogFormat, custom_formatter: Option<CustomLogFormatter>) {
+    match custom_formatter {
+        Some(formatter) => {
+            builder.format(move |buf, record| formatter(buf, record));
+        }
+        None => match format {
+            LogFormat::Default => {}
+            LogFormat::Compact => {
+                builder.format(compact_format);
+            }
+        },
+    }
+}
+
+/// The built-in `compact` format: one `key=value` pair per field on a single line, easy to grep
+/// or feed into line-oriented log tooling.
+fn compact_format(buf: &mut Formatter, record: &log::Record) -> std::io::Result<()> {
+    writeln!(
+        buf,
+        "level={} target={} msg={:?}",
+        record.level(),
+        record.target(),
+        record.args().to_string()
+    )
+}
+
+fn init_stderr_logger(
+    level: LevelFilter,
+    format: LogFormat,
+    filters: Option<&str>,
+    custom_formatter: Option<CustomLogFormatter>,
+) -> Result<(), String> {
+    let mut builder = Builder::new();
+    builder.parse_filters(&build_filter_directives(level, filters));
+    apply_format(&mut builder, format, custom_formatter);
+    builder.target(Target::Stderr);
+    builder.try_init().map_err(|e| format!("Failed to initialize logger: {}", e))?;
+    log::info!("Logger initialized (mode: stderr-terminal, level: {}).", level);
+    Ok(())
+}
+
+fn init_file_logger(
+    path: &str,
+    if_exists: IfExists,
+    max_size_bytes: Option<u64>,
+    max_backups: u32,
+    level: LevelFilter,
+    format: LogFormat,
+    filters: Option<&str>,
+    custom_formatter: Option<CustomLogFormatter>,
+) -> Result<(), String> {
+    let mut open_options = OpenOptions::new();
+    open_options.write(true);
+    match if_exists {
+        IfExists::Append => {
+            open_options.create(true).append(true);
+        }
+        IfExists::Truncate => {
+            open_options.create(true).truncate(true);
+        }
+        IfExists::Fail => {
+            open_options.create_new(true);
+        }
+    }
+    let file = open_options
+        .open(path)
+        .map_err(|e| format!("Failed to open log file '{}': {}", path, e))?;
+
+    let mut builder = Builder::new();
+    builder.parse_filters(&build_filter_directives(level, filters));
+    apply_format(&mut builder, format, custom_formatter);
+    match max_size_bytes {
+        Some(max_size_bytes) => {
+            let current_size = file
+                .metadata()
+                .map_err(|e| format!("Failed to read metadata for log file '{}': {}", path, e))?
+                .len();
+            let writer = RotatingFileWriter::new(PathBuf::from(path), file, current_size, max_size_bytes, max_backups);
+            builder.target(Target::Pipe(Box::new(writer) as Box<dyn Write + Send>));
+        }
+        None => {
+            builder.target(Target::Pipe(Box::new(file) as Box<dyn Write + Send>));
+        }
+    }
+    builder.try_init().map_err(|e| format!("Failed to initialize logger: {}", e))?;
+    log::info!("Logger initialized (mode: file, level: {}).", level);
+    Ok(())
+}
+
+/// A `Write` target for [`Target::Pipe`] that rotates the backing file once a write would push
+/// it past `max_size_bytes`: the current file is shifted to `<path>.1` (cascading existing
+/// backups up to `max_backups`, dropping the oldest), and a fresh file is opened in its place.
+/// The check/rotate/reopen sequence is serialized behind a mutex so concurrent log calls from
+/// multiple threads can't interleave and corrupt the rotation. A failed rename (e.g. the backup
+/// directory is on a different filesystem) is treated as non-fatal: rotation is skipped for that
+/// write and logging continues on the current file.
+struct RotatingFileWriter {
+    inner: Mutex<RotatingFileState>,
+}
+
+struct RotatingFileState {
+    path: PathBuf,
+    file: std::fs::File,
+    current_size: u64,
+    max_size_bytes: u64,
+    max_backups: u32,
+}
+
+impl RotatingFileWriter {
+    fn new(path: PathBuf, file: std::fs::File, current_size: u64, max_size_bytes: u64, max_backups: u32) -> Self {
+        RotatingFileWriter {
+            inner: Mutex::new(RotatingFileState {
+                path,
+                file,
+                current_size,
+                max_size_bytes,
+                max_backups,
+            }),
+        }
+    }
+}
+
+impl RotatingFileState {
+    fn backup_path(&self, generation: u32) -> PathBuf {
+        let mut file_name = self.path.as_os_str().to_os_string();
+        file_name.push(format!(".{}", generation));
+        PathBuf::from(file_name)
+    }
+
+    /// Shifts `path.{n}` up to `path.{n+1}` (oldest generation dropped) and reopens `path` fresh.
+    /// Returns an error without touching `self.file`/`self.current_size` if the rename that
+    /// vacates `path` itself fails, so the caller can fall back to the still-open current file.
+    fn rotate(&mut self) -> std::io::Result<()> {
+        if self.max_backups > 0 {
+            for generation in (1..self.max_backups).rev() {
+                let src = self.backup_path(generation);
+                if src.exists() {
+                    let _ = std::fs::rename(&src, self.backup_path(generation + 1));
+                }
+            }
+            std::fs::rename(&self.path, self.backup_path(1))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)?;
+        self.file = file;
+        self.current_size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut state = self.inner.lock().unwrap();
+        if state.current_size + buf.len() as u64 > state.max_size_bytes {
+            if let Err(e) = state.rotate() {
+                eprintln!(
+                    "Failed to rotate log file '{}': {}; continuing to write to the current file.",
+                    state.path.display(),
+                    e
+                );
+            }
+        }
+        let written = state.file.write(buf)?;
+        state.current_size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.lock().unwrap().file.flush()
+    }
+}
+
+/// Installs `systemd`'s `JournalLog` as the global logger, which tags each record with
+/// `CODE_FILE`/`CODE_LINE`/`CODE_FUNCTION` from the emitting call site alongside the usual
+/// priority and message fields. Only reachable where a journald socket exists.
+#[cfg(target_os = "linux")]
+fn try_init_journald(level: LevelFilter) -> Result<(), String> {
+    systemd::journal::JournalLog::init()
+        .map_err(|e| format!("failed to connect to the systemd-journald socket: {}", e))?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn try_init_journald(_level: LevelFilter) -> Result<(), String> {
+    Err("systemd-journald is only available on Linux".to_string())
+}
+
+/// Installs a `syslog` (RFC 3164) logger over the local Unix domain socket as the global logger.
+#[cfg(unix)]
+fn try_init_syslog(level: LevelFilter) -> Result<(), String> {
+    let formatter = syslog::Formatter3164 {
+        facility: syslog::Facility::LOG_USER,
+        hostname: None,
+        process: "dlogcover-rs".to_string(),
+        pid: std::process::id(),
+    };
+    let logger = syslog::unix(formatter).map_err(|e| format!("failed to connect to syslog: {}", e))?;
+    log::set_boxed_logger(Box::new(syslog::BasicLogger::new(logger)))
+        .map_err(|e| format!("failed to install syslog logger: {}", e))?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn try_init_syslog(_level: LevelFilter) -> Result<(), String> {
+    Err("syslog is only available on Unix targets".to_string())
 }
 
 #[allow(dead_code)]
-pub fn set_log_level(_level: LevelFilter) { // Prefixed level with _
-    log::warn!("Dynamic log level setting with env_logger is typically handled by RUST_LOG at startup. Current level: {:?}", log::max_level());
-    // log::set_max_level(_level); // This affects the global max level for the `log` facade.
+pub fn set_log_level(level: LevelFilter) {
+    log::warn!(
+        "Dynamic log level changes are not fully supported once the logger is initialized; raising `log::max_level()` to {:?}, but a backend-level filter below that level will still suppress records.",
+        level
+    );
+    log::set_max_level(level);
 }
 
 #[allow(dead_code)]
@@ -30,4 +351,3 @@ pub fn get_log_level() -> LevelFilter {
 pub fn shutdown_logger() {
     log::info!("Logger shutdown requested (env_logger typically auto-flushes).");
 }
-